@@ -0,0 +1,351 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::keys::{constants, Key};
+use crate::ModifierType;
+use glib::translate::*;
+use std::fmt;
+use std::str::FromStr;
+
+// The modifiers that can be part of an accelerator, in the order they are
+// written in.
+const MODIFIERS: &[(ModifierType, &str, &str)] = &[
+    (ModifierType::CONTROL_MASK, "<Control>", "Ctrl"),
+    (ModifierType::SHIFT_MASK, "<Shift>", "Shift"),
+    (ModifierType::ALT_MASK, "<Alt>", "Alt"),
+    (ModifierType::SUPER_MASK, "<Super>", "Super"),
+    (ModifierType::HYPER_MASK, "<Hyper>", "Hyper"),
+    (ModifierType::META_MASK, "<Meta>", "Meta"),
+];
+
+// Keys that are never accelerators on their own, as in `gtk_accelerator_valid`.
+const INVALID_KEYS: &[Key] = &[
+    constants::Shift_L,
+    constants::Shift_R,
+    constants::Shift_Lock,
+    constants::Caps_Lock,
+    constants::ISO_Lock,
+    constants::Control_L,
+    constants::Control_R,
+    constants::Meta_L,
+    constants::Meta_R,
+    constants::Alt_L,
+    constants::Alt_R,
+    constants::Super_L,
+    constants::Super_R,
+    constants::Hyper_L,
+    constants::Hyper_R,
+    constants::ISO_Level3_Shift,
+    constants::ISO_Next_Group,
+    constants::ISO_Prev_Group,
+    constants::ISO_First_Group,
+    constants::ISO_Last_Group,
+    constants::Mode_switch,
+    constants::Num_Lock,
+    constants::Multi_key,
+    constants::Scroll_Lock,
+    constants::Sys_Req,
+    constants::Tab,
+    constants::ISO_Left_Tab,
+    constants::KP_Tab,
+    constants::First_Virtual_Screen,
+    constants::Prev_Virtual_Screen,
+    constants::Next_Virtual_Screen,
+    constants::Last_Virtual_Screen,
+    constants::Terminate_Server,
+    constants::AudibleBell_Enable,
+];
+
+fn modifier_from_name(name: &str) -> Option<ModifierType> {
+    let modifier = match name.to_ascii_lowercase().as_str() {
+        "control" | "ctrl" | "ctl" => ModifierType::CONTROL_MASK,
+        "shift" | "shft" => ModifierType::SHIFT_MASK,
+        "alt" | "mod1" => ModifierType::ALT_MASK,
+        "super" => ModifierType::SUPER_MASK,
+        "hyper" => ModifierType::HYPER_MASK,
+        "meta" => ModifierType::META_MASK,
+        "primary" if cfg!(target_os = "macos") => ModifierType::META_MASK,
+        "primary" => ModifierType::CONTROL_MASK,
+        _ => return None,
+    };
+    Some(modifier)
+}
+
+fn key_from_name(name: &str) -> Option<Key> {
+    if let Some(key) = Key::from_name(name) {
+        return Some(key);
+    }
+    // Keys without a name are written as their hexadecimal value.
+    if let Some(hex) = name.strip_prefix("0x") {
+        return u32::from_str_radix(hex, 16)
+            .ok()
+            .map(|keyval| unsafe { from_glib(keyval) });
+    }
+    // Single characters without a key name, like `é`.
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => {
+            let key: Key = unsafe { from_glib(ffi::gdk_unicode_to_keyval(c as u32)) };
+            // Characters without a key map to `0x1000000 + c`, which is valid too.
+            Some(key)
+        }
+        _ => None,
+    }
+}
+
+// Translates `label` with the keyboard labels of GTK, when they are available.
+fn translate(label: &str) -> String {
+    unsafe {
+        glib::GString::from_glib_none(glib::ffi::g_dpgettext2(
+            b"gtk40\0".as_ptr() as *const _,
+            b"keyboard label\0".as_ptr() as *const _,
+            label.to_glib_none().0,
+        ))
+        .to_string()
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// A keyboard shortcut: a key and the modifiers held with it.
+///
+/// Accelerators are written like `<Control><Shift>s`, in the format of
+/// `gtk_accelerator_parse`. They only use the static key tables of GDK, so they
+/// can be parsed, compared and formatted before GTK is initialized, for
+/// example to validate a configuration file when it is loaded.
+///
+/// Accelerators are normalized when they are created: the key is lowercased
+/// and modifiers that can't be part of a shortcut, like Caps Lock, are
+/// dropped. `<Ctrl>S` and `<Control>s` are the same accelerator.
+///
+/// ```
+/// use gdk4 as gdk;
+///
+/// let accel: gdk::Accelerator = "<Ctrl><Shift>S".parse().unwrap();
+/// assert_eq!(accel.to_string(), "<Control><Shift>s");
+/// assert_eq!(accel, "<Shift><Control>s".parse().unwrap());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Accelerator {
+    key: Key,
+    modifiers: ModifierType,
+}
+
+impl Accelerator {
+    pub fn new(key: Key, modifiers: ModifierType) -> Self {
+        skip_assert_initialized!();
+        let mask = MODIFIERS
+            .iter()
+            .fold(ModifierType::empty(), |mask, (modifier, _, _)| {
+                mask | *modifier
+            });
+        Self {
+            key: key.to_lower(),
+            modifiers: modifiers & mask,
+        }
+    }
+
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    pub fn modifiers(&self) -> ModifierType {
+        self.modifiers
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Whether the accelerator can be used as a shortcut, like
+    /// `gtk_accelerator_valid`.
+    ///
+    /// Modifier keys on their own and keys used for keyboard navigation, like
+    /// Tab, are not valid.
+    pub fn is_valid(&self) -> bool {
+        !INVALID_KEYS.contains(&self.key) && self.key != constants::VoidSymbol
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The label of the accelerator to show to users, like `Ctrl+Shift+S`,
+    /// translated with the keyboard labels of GTK.
+    #[doc(alias = "gtk_accelerator_get_label")]
+    pub fn label(&self) -> String {
+        let mut parts: Vec<String> = MODIFIERS
+            .iter()
+            .filter(|(modifier, _, _)| self.modifiers.contains(*modifier))
+            .map(|(_, _, label)| translate(label))
+            .collect();
+        parts.push(self.key_label());
+        parts.join("+")
+    }
+
+    fn key_label(&self) -> String {
+        match self.key {
+            constants::space => return translate("Space"),
+            constants::backslash => return translate("Backslash"),
+            _ => (),
+        }
+        match self.key.to_unicode() {
+            Some(c) if !c.is_whitespace() && !c.is_control() => c.to_uppercase().collect(),
+            _ => match self.key.name() {
+                Some(name) => translate(&name.replace('_', " ")),
+                None => format!("{:#06x}", *self.key),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (modifier, name, _) in MODIFIERS {
+            if self.modifiers.contains(*modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.key.name() {
+            Some(name) => f.write_str(&name),
+            None => write!(f, "{:#x}", *self.key),
+        }
+    }
+}
+
+impl FromStr for Accelerator {
+    type Err = glib::BoolError;
+
+    #[doc(alias = "gtk_accelerator_parse")]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        skip_assert_initialized!();
+        let mut modifiers = ModifierType::empty();
+        let mut rest = s.trim();
+        while let Some(modifier) = rest.strip_prefix('<') {
+            let end = modifier
+                .find('>')
+                .ok_or_else(|| glib::bool_error!("Unclosed modifier in accelerator “{}”", s))?;
+            modifiers |= modifier_from_name(&modifier[..end]).ok_or_else(|| {
+                glib::bool_error!(
+                    "Unknown modifier “<{}>” in accelerator “{}”",
+                    &modifier[..end],
+                    s
+                )
+            })?;
+            rest = &modifier[end + 1..];
+        }
+        if rest.is_empty() {
+            return Err(glib::bool_error!("Missing key in accelerator “{}”", s));
+        }
+        let key = key_from_name(rest)
+            .ok_or_else(|| glib::bool_error!("Unknown key “{}” in accelerator “{}”", rest, s))?;
+        Ok(Self::new(key, modifiers))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Accelerator {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Accelerator {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Accelerator {
+        s.parse().unwrap()
+    }
+
+    fn parse_error(s: &str) -> String {
+        s.parse::<Accelerator>().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_error("<Control").starts_with("Unclosed modifier"));
+        assert!(parse_error("<Control><Shift s").starts_with("Unclosed modifier"));
+        assert!(parse_error("<Foo>a").starts_with("Unknown modifier “<Foo>”"));
+        assert!(parse_error("<>a").starts_with("Unknown modifier"));
+        assert!(parse_error("<Control>").starts_with("Missing key"));
+        assert!(parse_error("  ").starts_with("Missing key"));
+        assert!(parse_error("<Control>NoSuchKey").starts_with("Unknown key “NoSuchKey”"));
+        assert!(parse_error("0xzz").starts_with("Unknown key"));
+    }
+
+    #[test]
+    fn test_parse() {
+        let accel = parse("<Control><Alt>Delete");
+        assert_eq!(accel.key(), constants::Delete);
+        assert_eq!(
+            accel.modifiers(),
+            ModifierType::CONTROL_MASK | ModifierType::ALT_MASK
+        );
+        assert_eq!(
+            parse("<Primary>q").modifiers(),
+            modifier_from_name("primary").unwrap()
+        );
+        assert_eq!(parse("é").key(), constants::eacute);
+
+        // Keys without a name are written as their value.
+        let accel = parse("<Alt>0x1234");
+        assert_eq!(*accel.key(), 0x1234);
+        assert_eq!(accel.modifiers(), ModifierType::ALT_MASK);
+        assert_eq!(parse("0x1008ff13").key(), constants::AudioRaiseVolume);
+    }
+
+    #[test]
+    fn test_normalization() {
+        assert_eq!(parse("<Ctrl>S"), parse("<Control>s"));
+        assert_eq!(parse("<ctrl><SHIFT>a"), parse("<Shift><Control>A"));
+        assert_eq!(parse(" <Mod1>x "), parse("<Alt>x"));
+        let accel = Accelerator::new(
+            constants::A,
+            ModifierType::CONTROL_MASK | ModifierType::LOCK_MASK | ModifierType::BUTTON1_MASK,
+        );
+        assert_eq!(accel.key(), constants::a);
+        assert_eq!(accel.modifiers(), ModifierType::CONTROL_MASK);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(parse("<Shift><Ctrl>S").to_string(), "<Control><Shift>s");
+        assert_eq!(parse("<Alt>0x1234").to_string(), "<Alt>0x1234");
+        for s in [
+            "<Control><Shift>s",
+            "<Alt>F4",
+            "<Super>space",
+            "<Hyper><Meta>Return",
+            "Escape",
+            "<Control>eacute",
+            "<Alt>0x1234",
+        ] {
+            assert_eq!(parse(s).to_string(), s);
+            assert_eq!(parse(&parse(s).to_string()), parse(s));
+        }
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(parse("a").is_valid());
+        assert!(parse("<Control>s").is_valid());
+        assert!(parse("F1").is_valid());
+        assert!(!parse("Tab").is_valid());
+        assert!(!parse("<Control>Tab").is_valid());
+        assert!(!parse("<Control>Shift_L").is_valid());
+        assert!(!parse("Caps_Lock").is_valid());
+        assert!(!Accelerator::new(constants::VoidSymbol, ModifierType::empty()).is_valid());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_serde() {
+        let accel = parse("<Ctrl><Shift>S");
+        let json = serde_json::to_string(&accel).unwrap();
+        assert_eq!(json, r#""<Control><Shift>s""#);
+        assert_eq!(serde_json::from_str::<Accelerator>(&json).unwrap(), accel);
+        assert!(serde_json::from_str::<Accelerator>(r#""<Foo>s""#).is_err());
+        assert!(serde_json::from_str::<Accelerator>("42").is_err());
+    }
+}
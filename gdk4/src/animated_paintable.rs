@@ -0,0 +1,716 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use crate::subclass::prelude::ObjectSubclassExt;
+use crate::{FrameClock, Paintable, Texture};
+use std::time::Duration;
+
+type Decoder = Box<dyn FnMut() -> Option<(Texture, Duration)>>;
+
+mod imp {
+    use super::*;
+    use crate::subclass::prelude::*;
+    use crate::{PaintableFlags, Snapshot};
+    use glib::SignalHandlerId;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    pub struct State {
+        pub frames: Vec<(Texture, Duration)>,
+        // Produces the frames after the ones in `frames`, until it's done.
+        pub decoder: Option<Decoder>,
+        pub loop_count: Option<u32>,
+        pub current: usize,
+        pub playing: bool,
+        // The position when paused, or when `started` while playing.
+        pub position: Duration,
+        // The frame time of the first update since `position` was set.
+        pub started: Option<i64>,
+        pub clock: Option<(FrameClock, SignalHandlerId)>,
+    }
+
+    impl State {
+        fn decode_next(&mut self) -> bool {
+            match self.decoder.as_mut().and_then(|decoder| decoder()) {
+                Some(frame) => {
+                    self.frames.push(frame);
+                    true
+                }
+                None => {
+                    self.decoder = None;
+                    false
+                }
+            }
+        }
+
+        pub fn decode_all(&mut self) {
+            while self.decode_next() {}
+        }
+
+        pub fn loop_duration(&self) -> Option<Duration> {
+            if self.decoder.is_some() {
+                return None;
+            }
+            Some(self.frames.iter().map(|(_, duration)| *duration).sum())
+        }
+
+        // The frame shown at `position`, and whether the animation is over by
+        // then.
+        pub fn frame_at(&mut self, position: Duration) -> (usize, bool) {
+            if self.loop_count == Some(0) {
+                if self.frames.is_empty() {
+                    self.decode_next();
+                }
+                return (0, true);
+            }
+            let mut offset = position;
+            let mut index = 0;
+            // Within the first loop, frames are decoded as they are reached.
+            loop {
+                if index == self.frames.len() && !self.decode_next() {
+                    break;
+                }
+                let duration = self.frames[index].1;
+                if offset < duration {
+                    return (index, false);
+                }
+                offset -= duration;
+                index += 1;
+            }
+
+            let last = self.frames.len().saturating_sub(1);
+            let total = self.loop_duration().unwrap_or_default();
+            if total.is_zero() {
+                return (last, true);
+            }
+            let iteration = position.as_nanos() / total.as_nanos();
+            if let Some(loop_count) = self.loop_count {
+                if iteration >= loop_count as u128 {
+                    return (last, true);
+                }
+            }
+            let mut offset = Duration::from_nanos((position.as_nanos() % total.as_nanos()) as u64);
+            for (index, (_, duration)) in self.frames.iter().enumerate() {
+                if offset < *duration {
+                    return (index, false);
+                }
+                offset -= *duration;
+            }
+            (last, false)
+        }
+
+        pub fn position(&self) -> Duration {
+            match (&self.clock, self.started) {
+                (Some((clock, _)), Some(started)) if self.playing => {
+                    self.position
+                        + Duration::from_micros((clock.frame_time() - started).max(0) as u64)
+                }
+                _ => self.position,
+            }
+        }
+    }
+
+    #[derive(Default)]
+    pub struct AnimatedPaintable {
+        pub(super) state: RefCell<State>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AnimatedPaintable {
+        const NAME: &'static str = "GdkRsAnimatedPaintable";
+        type Type = super::AnimatedPaintable;
+        type Interfaces = (Paintable,);
+    }
+
+    impl ObjectImpl for AnimatedPaintable {
+        fn dispose(&self, paintable: &Self::Type) {
+            paintable.set_frame_clock(None);
+        }
+    }
+
+    impl PaintableImpl for AnimatedPaintable {
+        fn current_image(&self, _paintable: &Self::Type) -> Paintable {
+            let state = self.state.borrow();
+            match state.frames.get(state.current) {
+                Some((texture, _)) => texture.clone().upcast(),
+                None => Paintable::new_empty(0, 0).unwrap(),
+            }
+        }
+
+        fn flags(&self, _paintable: &Self::Type) -> PaintableFlags {
+            // Decoders draw every frame on the whole canvas of the animation.
+            PaintableFlags::SIZE
+        }
+
+        fn intrinsic_width(&self, _paintable: &Self::Type) -> i32 {
+            let state = self.state.borrow();
+            state
+                .frames
+                .first()
+                .map_or(0, |(texture, _)| texture.width())
+        }
+
+        fn intrinsic_height(&self, _paintable: &Self::Type) -> i32 {
+            let state = self.state.borrow();
+            state
+                .frames
+                .first()
+                .map_or(0, |(texture, _)| texture.height())
+        }
+
+        fn snapshot(&self, _paintable: &Self::Type, snapshot: &Snapshot, width: f64, height: f64) {
+            let state = self.state.borrow();
+            if let Some((texture, _)) = state.frames.get(state.current) {
+                texture.snapshot(snapshot, width, height);
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    // rustdoc-stripper-ignore-next
+    /// A [`Paintable`](crate::Paintable) playing an animation made of
+    /// textures, like an animated GIF.
+    ///
+    /// Frames are either given upfront or produced one at a time by a decoder,
+    /// which is only asked for a frame when the animation reaches it, so long
+    /// animations don't need to be decoded before they start playing. Decoded
+    /// frames are kept for the next loops.
+    ///
+    /// The animation advances with a [`FrameClock`](crate::FrameClock), which is
+    /// usually the frame clock of the widget showing it. It only moves while it
+    /// is playing and has a frame clock.
+    ///
+    /// With the `gif`, `apng` and `webp` features, animations can be decoded
+    /// from files with `AnimatedPaintable::from_bytes`.
+    ///
+    /// ```no_run
+    /// use gdk4 as gdk;
+    /// use std::time::Duration;
+    ///
+    /// # fn example(frames: Vec<gdk::Texture>, clock: &gdk::FrameClock) {
+    /// let paintable = gdk::AnimatedPaintable::new(
+    ///     frames
+    ///         .into_iter()
+    ///         .map(|texture| (texture, Duration::from_millis(40))),
+    /// );
+    /// paintable.set_loop_count(Some(3));
+    /// paintable.set_frame_clock(Some(clock));
+    /// paintable.play();
+    /// # }
+    /// ```
+    pub struct AnimatedPaintable(ObjectSubclass<imp::AnimatedPaintable>) @implements Paintable;
+}
+
+impl AnimatedPaintable {
+    // rustdoc-stripper-ignore-next
+    /// Creates a paintable showing each texture for its duration.
+    pub fn new(frames: impl IntoIterator<Item = (Texture, Duration)>) -> Self {
+        assert_initialized_main_thread!();
+        let paintable = Self::empty();
+        paintable.state().frames = frames.into_iter().collect();
+        paintable
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Creates a paintable showing the frames returned by `decoder`, until it
+    /// returns `None`.
+    ///
+    /// The first frame is decoded right away to know the size of the
+    /// animation.
+    pub fn from_decoder<F>(decoder: F) -> Self
+    where
+        F: FnMut() -> Option<(Texture, Duration)> + 'static,
+    {
+        assert_initialized_main_thread!();
+        let paintable = Self::empty();
+        {
+            let mut state = paintable.state();
+            state.decoder = Some(Box::new(decoder));
+            state.frame_at(Duration::ZERO);
+        }
+        paintable
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Decodes an animated GIF, PNG or WebP image, depending on the enabled
+    /// features.
+    ///
+    /// Frames are decoded as they are played. Frames that fail to decode end
+    /// the animation early, with a warning. The loop count is the one stored
+    /// in the image, so GIFs without a loop extension play once, like in
+    /// browsers.
+    #[cfg(any(feature = "apng", feature = "gif", feature = "webp"))]
+    #[cfg_attr(
+        feature = "dox",
+        doc(cfg(any(feature = "apng", feature = "gif", feature = "webp")))
+    )]
+    pub fn from_bytes(data: &[u8]) -> Result<Self, glib::Error> {
+        skip_assert_initialized!();
+        let loop_count = decode::loop_count(data);
+        let mut frames = decode::frames(data.to_vec())?;
+        let paintable = Self::from_decoder(move || decode::next_frame(&mut frames));
+        paintable.set_loop_count(loop_count);
+        Ok(paintable)
+    }
+
+    fn empty() -> Self {
+        glib::Object::new(&[]).expect("Failed to create AnimatedPaintable")
+    }
+
+    fn state(&self) -> std::cell::RefMut<imp::State> {
+        imp::AnimatedPaintable::from_instance(self)
+            .state
+            .borrow_mut()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Sets the frame clock driving the animation, or `None` to freeze it.
+    ///
+    /// Use the frame clock of the widget showing the paintable, which exists
+    /// while the widget is realized.
+    pub fn set_frame_clock(&self, clock: Option<&FrameClock>) {
+        let mut state = self.state();
+        state.position = state.position();
+        state.started = None;
+        if let Some((clock, handler)) = state.clock.take() {
+            if state.playing {
+                clock.end_updating();
+            }
+            clock.disconnect(handler);
+        }
+        if let Some(clock) = clock {
+            let paintable = self.downgrade();
+            let handler = clock.connect_update(move |clock| {
+                if let Some(paintable) = paintable.upgrade() {
+                    paintable.tick(clock);
+                }
+            });
+            if state.playing {
+                clock.begin_updating();
+            }
+            state.clock = Some((clock.clone(), handler));
+        }
+    }
+
+    pub fn frame_clock(&self) -> Option<FrameClock> {
+        self.state().clock.as_ref().map(|(clock, _)| clock.clone())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Sets how many times the animation plays, or `None` to loop forever,
+    /// which is the default.
+    ///
+    /// With `Some(0)`, the animation never plays and shows its first frame.
+    pub fn set_loop_count(&self, loop_count: Option<u32>) {
+        self.state().loop_count = loop_count;
+    }
+
+    pub fn loop_count(&self) -> Option<u32> {
+        self.state().loop_count
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.state().playing
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Starts or resumes the animation, from the start if it was over.
+    pub fn play(&self) {
+        let mut state = self.state();
+        if state.playing {
+            return;
+        }
+        let position = state.position;
+        if state.frame_at(position).1 {
+            state.position = Duration::ZERO;
+        }
+        state.playing = true;
+        state.started = None;
+        if let Some((clock, _)) = &state.clock {
+            clock.begin_updating();
+        }
+    }
+
+    pub fn pause(&self) {
+        let mut state = self.state();
+        if !state.playing {
+            return;
+        }
+        state.position = state.position();
+        state.playing = false;
+        state.started = None;
+        if let Some((clock, _)) = &state.clock {
+            clock.end_updating();
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Moves the animation to `position`, counted from the start of the first
+    /// loop.
+    pub fn seek(&self, position: Duration) {
+        {
+            let mut state = self.state();
+            state.position = position;
+            state.started = None;
+        }
+        self.show_frame_at(position);
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The time since the start of the first loop.
+    pub fn position(&self) -> Duration {
+        self.state().position()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The duration of one loop of the animation.
+    ///
+    /// It's only known once all the frames are decoded, which
+    /// [`Self::decode_all`] forces.
+    pub fn duration(&self) -> Option<Duration> {
+        self.state().loop_duration()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Decodes all the remaining frames at once.
+    pub fn decode_all(&self) {
+        self.state().decode_all();
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The number of frames decoded so far.
+    pub fn n_frames(&self) -> usize {
+        self.state().frames.len()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The texture currently shown.
+    pub fn current_texture(&self) -> Option<Texture> {
+        let state = self.state();
+        state
+            .frames
+            .get(state.current)
+            .map(|(texture, _)| texture.clone())
+    }
+
+    fn tick(&self, clock: &FrameClock) {
+        let position = {
+            let mut state = self.state();
+            if !state.playing {
+                return;
+            }
+            if state.started.is_none() {
+                state.started = Some(clock.frame_time());
+            }
+            state.position()
+        };
+        if self.show_frame_at(position) {
+            let mut state = self.state();
+            state.position = position;
+            state.playing = false;
+            state.started = None;
+            clock.end_updating();
+        }
+    }
+
+    // Shows the frame at `position`, and returns whether the animation is
+    // over.
+    fn show_frame_at(&self, position: Duration) -> bool {
+        let (changed, finished) = {
+            let mut state = self.state();
+            let (index, finished) = state.frame_at(position);
+            let changed = index != state.current;
+            state.current = index;
+            (changed, finished)
+        };
+        if changed {
+            self.invalidate_contents();
+        }
+        finished
+    }
+}
+
+#[cfg(any(feature = "apng", feature = "gif", feature = "webp"))]
+mod decode {
+    use crate::prelude::*;
+    use crate::{Texture, TextureData};
+    use image::{AnimationDecoder, Frames, ImageError, ImageFormat};
+    use std::convert::TryFrom;
+    use std::io::Cursor;
+    use std::time::Duration;
+
+    fn to_glib_error(err: ImageError) -> glib::Error {
+        glib::Error::new(gio::IOErrorEnum::InvalidData, &err.to_string())
+    }
+
+    pub fn frames(data: Vec<u8>) -> Result<Frames<'static>, glib::Error> {
+        let format = image::guess_format(&data).map_err(to_glib_error)?;
+        let data = Cursor::new(data);
+        let frames = match format {
+            #[cfg(feature = "gif")]
+            ImageFormat::Gif => image::codecs::gif::GifDecoder::new(data)
+                .map_err(to_glib_error)?
+                .into_frames(),
+            #[cfg(feature = "apng")]
+            ImageFormat::Png => image::codecs::png::PngDecoder::new(data)
+                .map_err(to_glib_error)?
+                .apng()
+                .into_frames(),
+            #[cfg(feature = "webp")]
+            ImageFormat::WebP => image::codecs::webp::WebPDecoder::new(data)
+                .map_err(to_glib_error)?
+                .into_frames(),
+            _ => {
+                return Err(glib::Error::new(
+                    gio::IOErrorEnum::NotSupported,
+                    &format!("Unsupported animation format {:?}", format),
+                ))
+            }
+        };
+        Ok(frames)
+    }
+
+    // The number of times the animation plays, from the loop count stored in
+    // the file, where 0 means forever.
+    pub fn loop_count(data: &[u8]) -> Option<u32> {
+        if data.starts_with(b"GIF8") {
+            gif_loop_count(data)
+        } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            apng_loop_count(data)
+        } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+            webp_loop_count(data)
+        } else {
+            None
+        }
+    }
+
+    // The NETSCAPE2.0 application extension counts the repetitions after the
+    // first play. Without it, the animation plays once.
+    fn gif_loop_count(data: &[u8]) -> Option<u32> {
+        let flags = *data.get(10)?;
+        let mut pos = 13;
+        if flags & 0x80 != 0 {
+            pos += 3 << ((flags & 0x07) + 1);
+        }
+        // The extension comes before the first image.
+        while data.get(pos) == Some(&0x21) {
+            let label = *data.get(pos + 1)?;
+            pos += 2;
+            let mut blocks = Vec::new();
+            loop {
+                let len = *data.get(pos)? as usize;
+                pos += 1;
+                if len == 0 {
+                    break;
+                }
+                blocks.push(data.get(pos..pos + len)?);
+                pos += len;
+            }
+            if label == 0xff
+                && matches!(
+                    blocks.first(),
+                    Some(&b"NETSCAPE2.0") | Some(&b"ANIMEXTS1.0")
+                )
+            {
+                return match blocks.get(1).copied() {
+                    Some(&[1, 0, 0]) => None,
+                    Some(&[1, low, high]) => Some(u16::from_le_bytes([low, high]) as u32 + 1),
+                    _ => Some(1),
+                };
+            }
+        }
+        Some(1)
+    }
+
+    // The acTL chunk, which comes before the image data.
+    fn apng_loop_count(data: &[u8]) -> Option<u32> {
+        let mut pos = 8;
+        while let Some(header) = data.get(pos..pos + 8) {
+            let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+            match &header[4..] {
+                b"acTL" => {
+                    let plays = data.get(pos + 12..pos + 16)?;
+                    return match u32::from_be_bytes([plays[0], plays[1], plays[2], plays[3]]) {
+                        0 => None,
+                        plays => Some(plays),
+                    };
+                }
+                b"IDAT" => return None,
+                _ => pos += 12 + len,
+            }
+        }
+        None
+    }
+
+    // The ANIM chunk of extended WebP files.
+    fn webp_loop_count(data: &[u8]) -> Option<u32> {
+        let mut pos = 12;
+        while let Some(header) = data.get(pos..pos + 8) {
+            let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+            if &header[..4] == b"ANIM" {
+                let count = data.get(pos + 12..pos + 14)?;
+                return match u16::from_le_bytes([count[0], count[1]]) {
+                    0 => None,
+                    count => Some(count as u32),
+                };
+            }
+            // Chunks are padded to an even size.
+            pos += 8 + len + (len & 1);
+        }
+        None
+    }
+
+    pub fn next_frame(frames: &mut Frames<'static>) -> Option<(Texture, Duration)> {
+        let frame = match frames.next()? {
+            Ok(frame) => frame,
+            Err(err) => {
+                glib::g_warning!("Gdk", "Failed to decode animation frame: {}", err);
+                return None;
+            }
+        };
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let mut duration = Duration::from_micros(numer as u64 * 1000 / denom.max(1) as u64);
+        // Like browsers, show frames without a meaningful delay for 100ms.
+        if duration <= Duration::from_millis(10) {
+            duration = Duration::from_millis(100);
+        }
+        let texture = TextureData::try_from(frame.buffer()).ok()?.to_texture();
+        Some((texture.upcast(), duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::imp::State;
+    use super::*;
+    use crate::TextureData;
+
+    fn state(durations: &[u64], loop_count: Option<u32>) -> State {
+        let frames = durations
+            .iter()
+            .map(|&ms| {
+                let texture = TextureData::from_rgba8(1, 1, &[[0, 0, 0, 255]])
+                    .unwrap()
+                    .to_texture();
+                (texture.upcast(), Duration::from_millis(ms))
+            })
+            .collect();
+        State {
+            frames,
+            loop_count,
+            ..State::default()
+        }
+    }
+
+    fn frame_at(state: &mut State, ms: u64) -> (usize, bool) {
+        state.frame_at(Duration::from_millis(ms))
+    }
+
+    #[test]
+    fn test_frame_at() {
+        let mut state = state(&[100, 50, 100], None);
+        assert_eq!(frame_at(&mut state, 0), (0, false));
+        assert_eq!(frame_at(&mut state, 99), (0, false));
+        assert_eq!(frame_at(&mut state, 100), (1, false));
+        assert_eq!(frame_at(&mut state, 249), (2, false));
+        // Looping forever wraps around.
+        assert_eq!(frame_at(&mut state, 250), (0, false));
+        assert_eq!(frame_at(&mut state, 10 * 250 + 120), (1, false));
+    }
+
+    #[test]
+    fn test_frame_at_loop_count() {
+        let mut state = state(&[100, 100], Some(2));
+        assert_eq!(frame_at(&mut state, 250), (0, false));
+        assert_eq!(frame_at(&mut state, 399), (1, false));
+        // Seeking past the end shows the last frame.
+        assert_eq!(frame_at(&mut state, 400), (1, true));
+        assert_eq!(frame_at(&mut state, 10_000), (1, true));
+
+        // A loop count of zero never plays.
+        state.loop_count = Some(0);
+        assert_eq!(frame_at(&mut state, 0), (0, true));
+        assert_eq!(frame_at(&mut state, 150), (0, true));
+    }
+
+    #[test]
+    fn test_frame_at_zero_duration() {
+        // Frames without a duration are never shown.
+        let mut state = state(&[0, 100, 0, 100], None);
+        assert_eq!(frame_at(&mut state, 0), (1, false));
+        assert_eq!(frame_at(&mut state, 100), (3, false));
+        assert_eq!(frame_at(&mut state, 200), (1, false));
+
+        // An animation without any duration is over right away.
+        let mut state = state(&[0, 0], None);
+        assert_eq!(frame_at(&mut state, 0), (1, true));
+
+        let mut state = state(&[], None);
+        assert_eq!(frame_at(&mut state, 0), (0, true));
+    }
+
+    #[test]
+    fn test_frame_at_decoder() {
+        let mut state = state(&[], Some(1));
+        let mut remaining = 3;
+        let texture: Texture = TextureData::from_rgba8(1, 1, &[[0, 0, 0, 255]])
+            .unwrap()
+            .to_texture()
+            .upcast();
+        state.decoder = Some(Box::new(move || {
+            remaining = remaining.checked_sub(1)?;
+            Some((texture.clone(), Duration::from_millis(100)))
+        }));
+
+        // Frames are only decoded when they are reached.
+        assert_eq!(frame_at(&mut state, 150), (1, false));
+        assert_eq!(state.frames.len(), 2);
+        assert_eq!(state.loop_duration(), None);
+        assert_eq!(frame_at(&mut state, 300), (2, true));
+        assert_eq!(state.loop_duration(), Some(Duration::from_millis(300)));
+    }
+
+    #[cfg(any(feature = "apng", feature = "gif", feature = "webp"))]
+    #[test]
+    fn test_loop_count() {
+        let gif = |extension: &[u8]| {
+            let mut data = b"GIF89a\x01\x00\x01\x00\x80\x00\x00".to_vec();
+            // A global color table of two colors, and a graphic control extension.
+            data.extend_from_slice(&[0; 6]);
+            data.extend_from_slice(&[0x21, 0xf9, 4, 0, 0, 0, 0, 0]);
+            data.extend_from_slice(extension);
+            data.extend_from_slice(&[0x2c, 0, 0]);
+            data
+        };
+        let netscape = |count: u16| {
+            let mut extension = b"\x21\xff\x0bNETSCAPE2.0\x03\x01".to_vec();
+            extension.extend_from_slice(&count.to_le_bytes());
+            extension.push(0);
+            extension
+        };
+        assert_eq!(decode::loop_count(&gif(&[])), Some(1));
+        assert_eq!(decode::loop_count(&gif(&netscape(0))), None);
+        assert_eq!(decode::loop_count(&gif(&netscape(2))), Some(3));
+
+        let chunk = |kind: &[u8], data: &[u8]| {
+            let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+            chunk.extend_from_slice(kind);
+            chunk.extend_from_slice(data);
+            chunk.extend_from_slice(&[0; 4]);
+            chunk
+        };
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend(chunk(b"IHDR", &[0; 13]));
+        let mut apng = png.clone();
+        apng.extend(chunk(b"acTL", &[0, 0, 0, 2, 0, 0, 0, 4]));
+        assert_eq!(decode::loop_count(&apng), Some(4));
+        png.extend(chunk(b"IDAT", &[]));
+        assert_eq!(decode::loop_count(&png), None);
+
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0".to_vec();
+        webp.extend_from_slice(&[0; 10]);
+        webp.extend_from_slice(b"ANIM\x06\0\0\0\0\0\0\0\x05\0");
+        assert_eq!(decode::loop_count(&webp), Some(5));
+    }
+}
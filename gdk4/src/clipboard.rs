@@ -1,8 +1,16 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+#[cfg(feature = "serde")]
+use crate::content_codec::ContentCodec;
+#[cfg(feature = "serde")]
+use crate::prelude::*;
+#[cfg(feature = "serde")]
+use crate::ContentProvider;
 use crate::{Clipboard, Texture};
 use glib::object::IsA;
 use glib::translate::*;
+#[cfg(feature = "serde")]
+use glib::value::FromValue;
 use glib::{GString, ToValue};
 use std::future;
 use std::pin::Pin;
@@ -293,3 +301,95 @@ impl Clipboard {
         }))
     }
 }
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "dox", doc(cfg(feature = "serde")))]
+impl Clipboard {
+    // rustdoc-stripper-ignore-next
+    /// Puts `value` on the clipboard, and offers it to other processes as JSON
+    /// under `mime_type`.
+    ///
+    /// Readers in this process get the value itself. For other processes, the
+    /// value is encoded by a serializer registered with
+    /// [`content_register_serde_serializer`](crate::content_register_serde_serializer),
+    /// only when they ask for it. Read it back with [`Self::read_serde`].
+    ///
+    /// ```no_run
+    /// use gdk4 as gdk;
+    /// use gdk::{glib, prelude::*};
+    ///
+    /// #[derive(Clone, glib::Boxed, serde::Serialize, serde::Deserialize)]
+    /// #[boxed_type(name = "MyAppShape")]
+    /// struct Shape {
+    ///     points: Vec<(f64, f64)>,
+    /// }
+    ///
+    /// # async fn example(clipboard: &gdk::Clipboard) -> Result<(), glib::Error> {
+    /// let shape = Shape { points: vec![(0.0, 0.0), (1.0, 1.0)] };
+    /// clipboard.set_serde("application/x-myapp-shape", &shape)?;
+    ///
+    /// let pasted: Shape = clipboard.read_serde("application/x-myapp-shape").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(feature = "dox", doc(cfg(feature = "json")))]
+    pub fn set_serde<T>(&self, mime_type: &str, value: &T) -> Result<(), glib::Error>
+    where
+        T: for<'a> FromValue<'a> + ToValue + StaticType + serde::Serialize + 'static,
+    {
+        self.set_serde_with::<crate::JsonCodec, T>(mime_type, value)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Like [`Self::set_serde`], encoding the value with `C`.
+    pub fn set_serde_with<C, T>(&self, mime_type: &str, value: &T) -> Result<(), glib::Error>
+    where
+        C: ContentCodec + 'static,
+        T: for<'a> FromValue<'a> + ToValue + StaticType + serde::Serialize + 'static,
+    {
+        crate::content_register_serde_serializer::<C, T>(mime_type);
+        let provider = ContentProvider::for_value(&value.to_value());
+        self.set_content(Some(&provider))
+            .map_err(|err| glib::Error::new(gio::IOErrorEnum::Failed, &err.message))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Reads a value put on the clipboard with [`Self::set_serde`], by this
+    /// process or as JSON under `mime_type` by another one.
+    #[cfg(feature = "json")]
+    #[cfg_attr(feature = "dox", doc(cfg(feature = "json")))]
+    pub fn read_serde<T>(
+        &self,
+        mime_type: &str,
+    ) -> Pin<Box<dyn future::Future<Output = Result<T, glib::Error>> + 'static>>
+    where
+        T: for<'a> FromValue<'a> + ToValue + StaticType + serde::de::DeserializeOwned + 'static,
+    {
+        self.read_serde_with::<crate::JsonCodec, T>(mime_type)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Like [`Self::read_serde`], decoding the value with `C`.
+    ///
+    /// The decoder is registered with
+    /// [`content_register_serde_deserializer`](crate::content_register_serde_deserializer),
+    /// so the first codec used for a type and MIME type is the one used for
+    /// all reads.
+    pub fn read_serde_with<C, T>(
+        &self,
+        mime_type: &str,
+    ) -> Pin<Box<dyn future::Future<Output = Result<T, glib::Error>> + 'static>>
+    where
+        C: ContentCodec + 'static,
+        T: for<'a> FromValue<'a> + ToValue + StaticType + serde::de::DeserializeOwned + 'static,
+    {
+        crate::content_register_serde_deserializer::<C, T>(mime_type);
+        let read = self.read_value_future(T::static_type(), glib::PRIORITY_DEFAULT);
+        Box::pin(async move {
+            read.await?
+                .get::<T>()
+                .map_err(|err| glib::Error::new(gio::IOErrorEnum::InvalidData, &err.to_string()))
+        })
+    }
+}
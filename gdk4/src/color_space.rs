@@ -0,0 +1,335 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::RGBA;
+
+// rustdoc-stripper-ignore-next
+/// A color in the HSL color space.
+///
+/// `hue` is in degrees, in the range `0.0..360.0`; `saturation` and `lightness`
+/// are in the range `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hsl {
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+}
+
+// rustdoc-stripper-ignore-next
+/// A color in the HSV color space.
+///
+/// `hue` is in degrees, in the range `0.0..360.0`; `saturation` and `value` are
+/// in the range `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hsv {
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+// rustdoc-stripper-ignore-next
+/// A color in the linear sRGB color space, without the sRGB transfer function.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearRgb {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+}
+
+// rustdoc-stripper-ignore-next
+/// A color in the [OKLab](https://bottosson.github.io/posts/oklab/) color space.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OkLab {
+    pub lightness: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+// rustdoc-stripper-ignore-next
+/// A color in the OKLCH color space, the polar form of [`OkLab`].
+///
+/// `hue` is in degrees, in the range `0.0..360.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OkLch {
+    pub lightness: f32,
+    pub chroma: f32,
+    pub hue: f32,
+}
+
+fn to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+// The hue in degrees of an RGB color, along with its maximum and minimum
+// components.
+fn hue(red: f32, green: f32, blue: f32) -> (f32, f32, f32) {
+    let max = red.max(green).max(blue);
+    let min = red.min(green).min(blue);
+    let delta = max - min;
+    let hue = if delta <= 0.0 {
+        0.0
+    } else if max == red {
+        60.0 * ((green - blue) / delta).rem_euclid(6.0)
+    } else if max == green {
+        60.0 * ((blue - red) / delta + 2.0)
+    } else {
+        60.0 * ((red - green) / delta + 4.0)
+    };
+    (hue, max, min)
+}
+
+// The RGB components of a hue in degrees at full saturation, given the chroma
+// and the value to add to every component.
+fn from_hue(hue: f32, chroma: f32, offset: f32, alpha: f32) -> RGBA {
+    let hue = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (hue.rem_euclid(2.0) - 1.0).abs());
+    let (red, green, blue) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    RGBA::new(red + offset, green + offset, blue + offset, alpha)
+}
+
+impl Hsl {
+    pub fn to_rgba(&self, alpha: f32) -> RGBA {
+        let chroma = (1.0 - (2.0 * self.lightness - 1.0).abs()) * self.saturation;
+        from_hue(self.hue, chroma, self.lightness - chroma / 2.0, alpha)
+    }
+}
+
+impl Hsv {
+    pub fn to_rgba(&self, alpha: f32) -> RGBA {
+        let chroma = self.value * self.saturation;
+        from_hue(self.hue, chroma, self.value - chroma, alpha)
+    }
+}
+
+impl LinearRgb {
+    pub fn to_rgba(&self, alpha: f32) -> RGBA {
+        RGBA::new(
+            from_linear(self.red),
+            from_linear(self.green),
+            from_linear(self.blue),
+            alpha,
+        )
+    }
+}
+
+impl OkLab {
+    pub fn to_linear_rgb(&self) -> LinearRgb {
+        let l = self.lightness + 0.396_337_78 * self.a + 0.215_803_76 * self.b;
+        let m = self.lightness - 0.105_561_346 * self.a - 0.063_854_17 * self.b;
+        let s = self.lightness - 0.089_484_18 * self.a - 1.291_485_5 * self.b;
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+        LinearRgb {
+            red: 4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            green: -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            blue: -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        }
+    }
+
+    pub fn to_rgba(&self, alpha: f32) -> RGBA {
+        self.to_linear_rgb().to_rgba(alpha)
+    }
+
+    pub fn to_oklch(&self) -> OkLch {
+        OkLch {
+            lightness: self.lightness,
+            chroma: self.a.hypot(self.b),
+            hue: self.b.atan2(self.a).to_degrees().rem_euclid(360.0),
+        }
+    }
+}
+
+impl OkLch {
+    pub fn to_oklab(&self) -> OkLab {
+        let hue = self.hue.to_radians();
+        OkLab {
+            lightness: self.lightness,
+            a: self.chroma * hue.cos(),
+            b: self.chroma * hue.sin(),
+        }
+    }
+
+    pub fn to_rgba(&self, alpha: f32) -> RGBA {
+        self.to_oklab().to_rgba(alpha)
+    }
+}
+
+impl RGBA {
+    // rustdoc-stripper-ignore-next
+    /// Converts the color to HSL, ignoring the alpha channel.
+    pub fn to_hsl(&self) -> Hsl {
+        let (hue, max, min) = hue(self.red(), self.green(), self.blue());
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        Hsl {
+            hue,
+            saturation,
+            lightness,
+        }
+    }
+
+    pub fn from_hsl(hsl: &Hsl, alpha: f32) -> RGBA {
+        skip_assert_initialized!();
+        hsl.to_rgba(alpha)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Converts the color to HSV, ignoring the alpha channel.
+    pub fn to_hsv(&self) -> Hsv {
+        let (hue, max, min) = hue(self.red(), self.green(), self.blue());
+        let saturation = if max <= 0.0 { 0.0 } else { (max - min) / max };
+        Hsv {
+            hue,
+            saturation,
+            value: max,
+        }
+    }
+
+    pub fn from_hsv(hsv: &Hsv, alpha: f32) -> RGBA {
+        skip_assert_initialized!();
+        hsv.to_rgba(alpha)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Removes the sRGB transfer function from the color, ignoring the alpha
+    /// channel.
+    pub fn to_linear_rgb(&self) -> LinearRgb {
+        LinearRgb {
+            red: to_linear(self.red()),
+            green: to_linear(self.green()),
+            blue: to_linear(self.blue()),
+        }
+    }
+
+    pub fn from_linear_rgb(rgb: &LinearRgb, alpha: f32) -> RGBA {
+        skip_assert_initialized!();
+        rgb.to_rgba(alpha)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Converts the color to OKLab, ignoring the alpha channel.
+    pub fn to_oklab(&self) -> OkLab {
+        let LinearRgb { red, green, blue } = self.to_linear_rgb();
+        let l = (0.412_221_46 * red + 0.536_332_55 * green + 0.051_445_995 * blue).cbrt();
+        let m = (0.211_903_5 * red + 0.680_699_5 * green + 0.107_396_96 * blue).cbrt();
+        let s = (0.088_302_46 * red + 0.281_718_85 * green + 0.629_978_7 * blue).cbrt();
+        OkLab {
+            lightness: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        }
+    }
+
+    pub fn from_oklab(lab: &OkLab, alpha: f32) -> RGBA {
+        skip_assert_initialized!();
+        lab.to_rgba(alpha)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Converts the color to OKLCH, ignoring the alpha channel.
+    pub fn to_oklch(&self) -> OkLch {
+        self.to_oklab().to_oklch()
+    }
+
+    pub fn from_oklch(lch: &OkLch, alpha: f32) -> RGBA {
+        skip_assert_initialized!();
+        lch.to_rgba(alpha)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{} != {}", a, b);
+    }
+
+    fn assert_rgba_close(a: &RGBA, b: &RGBA) {
+        assert_close(a.red(), b.red());
+        assert_close(a.green(), b.green());
+        assert_close(a.blue(), b.blue());
+        assert_close(a.alpha(), b.alpha());
+    }
+
+    #[test]
+    fn test_hsl() {
+        let hsl = RGBA::RED.to_hsl();
+        assert_close(hsl.hue, 0.0);
+        assert_close(hsl.saturation, 1.0);
+        assert_close(hsl.lightness, 0.5);
+
+        let hsl = RGBA::new(0.2, 0.4, 0.8, 1.0).to_hsl();
+        assert_close(hsl.hue, 220.0);
+        assert_close(hsl.saturation, 0.6);
+        assert_close(hsl.lightness, 0.5);
+        assert_rgba_close(&hsl.to_rgba(0.5), &RGBA::new(0.2, 0.4, 0.8, 0.5));
+    }
+
+    #[test]
+    fn test_hsv() {
+        let hsv = RGBA::new(0.5, 1.0, 0.5, 1.0).to_hsv();
+        assert_close(hsv.hue, 120.0);
+        assert_close(hsv.saturation, 0.5);
+        assert_close(hsv.value, 1.0);
+        assert_rgba_close(&hsv.to_rgba(1.0), &RGBA::new(0.5, 1.0, 0.5, 1.0));
+
+        let hsv = RGBA::BLACK.to_hsv();
+        assert_close(hsv.saturation, 0.0);
+        assert_close(hsv.value, 0.0);
+    }
+
+    #[test]
+    fn test_linear_rgb() {
+        let rgb = RGBA::new(0.5, 0.02, 1.0, 1.0).to_linear_rgb();
+        assert_close(rgb.red, 0.214_041);
+        assert_close(rgb.green, 0.001_548);
+        assert_close(rgb.blue, 1.0);
+        assert_rgba_close(&rgb.to_rgba(1.0), &RGBA::new(0.5, 0.02, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_oklab() {
+        let lab = RGBA::WHITE.to_oklab();
+        assert_close(lab.lightness, 1.0);
+        assert_close(lab.a, 0.0);
+        assert_close(lab.b, 0.0);
+
+        let lab = RGBA::RED.to_oklab();
+        assert_close(lab.lightness, 0.627_955);
+        assert_close(lab.a, 0.224_863);
+        assert_close(lab.b, 0.125_846);
+        assert_rgba_close(&lab.to_rgba(1.0), &RGBA::RED);
+
+        let lch = RGBA::RED.to_oklch();
+        assert_close(lch.chroma, 0.257_683);
+        assert_close(lch.hue, 29.233_88);
+        assert_rgba_close(&lch.to_rgba(1.0), &RGBA::RED);
+    }
+}
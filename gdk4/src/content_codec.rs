@@ -0,0 +1,135 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use glib::value::FromValue;
+use glib::Type;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::error::Error;
+
+// rustdoc-stripper-ignore-next
+/// The encoding of Rust values exchanged through the clipboard or drag and
+/// drop, for example with [`Clipboard::set_serde_with`](crate::Clipboard::set_serde_with).
+pub trait ContentCodec {
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>>;
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Box<dyn Error + Send + Sync>>;
+}
+
+// rustdoc-stripper-ignore-next
+/// Encodes values as JSON, the default [`ContentCodec`].
+#[cfg(feature = "json")]
+#[cfg_attr(feature = "dox", doc(cfg(feature = "json")))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonCodec;
+
+#[cfg(feature = "json")]
+impl ContentCodec for JsonCodec {
+    fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        Ok(serde_json::to_vec(value)?)
+    }
+
+    fn decode<T: DeserializeOwned>(data: &[u8]) -> Result<T, Box<dyn Error + Send + Sync>> {
+        Ok(serde_json::from_slice(data)?)
+    }
+}
+
+// Codec errors are reported like the other content exchange errors of GDK.
+pub(crate) fn to_glib_error(err: Box<dyn Error + Send + Sync>) -> glib::Error {
+    glib::Error::new(gio::IOErrorEnum::InvalidData, &err.to_string())
+}
+
+thread_local! {
+    static SERIALIZERS: RefCell<HashSet<(Type, String)>> = RefCell::new(HashSet::new());
+    static DESERIALIZERS: RefCell<HashSet<(Type, String)>> = RefCell::new(HashSet::new());
+}
+
+// rustdoc-stripper-ignore-next
+/// Registers a [`content_register_serializer`](crate::content_register_serializer)
+/// that encodes values of type `T` with `C` under `mime_type`.
+///
+/// Content providers holding a `T`, like the ones of
+/// [`Clipboard::set_serde`](crate::Clipboard::set_serde), then offer it to
+/// other processes as `mime_type` too. The serializer is registered once per
+/// type and MIME type, so the first codec used for them is the one used for
+/// all transfers.
+pub fn content_register_serde_serializer<C, T>(mime_type: &str)
+where
+    C: ContentCodec + 'static,
+    T: for<'a> FromValue<'a> + StaticType + Serialize + 'static,
+{
+    assert_initialized_main_thread!();
+    let key = (T::static_type(), mime_type.to_owned());
+    if !SERIALIZERS.with(|registered| registered.borrow_mut().insert(key)) {
+        return;
+    }
+
+    crate::content_register_serializer::<(), _>(T::static_type(), mime_type, |serializer, _| {
+        let serializer = serializer.clone();
+        glib::MainContext::default().spawn_local(async move {
+            let result = match C::encode(&serializer.value_as::<T>()) {
+                Ok(data) => serializer
+                    .output_stream()
+                    .write_all_future(data, serializer.priority())
+                    .await
+                    .map(|_| ())
+                    .map_err(|(_, err)| err),
+                Err(err) => Err(to_glib_error(err)),
+            };
+            match result {
+                Ok(()) => serializer.return_success(),
+                Err(err) => serializer.return_error(err),
+            }
+        });
+    });
+}
+
+// rustdoc-stripper-ignore-next
+/// Registers a [`content_register_deserializer`](crate::content_register_deserializer)
+/// that decodes values of type `T` from `mime_type` with `C`.
+///
+/// Reading a `T` from a clipboard or a drop, like
+/// [`Clipboard::read_serde`](crate::Clipboard::read_serde) does, then also
+/// accepts data sent as `mime_type` by other processes. The deserializer is
+/// registered once per type and MIME type, so the first codec used for them
+/// is the one used for all transfers.
+pub fn content_register_serde_deserializer<C, T>(mime_type: &str)
+where
+    C: ContentCodec + 'static,
+    T: ToValue + StaticType + DeserializeOwned + 'static,
+{
+    assert_initialized_main_thread!();
+    let key = (T::static_type(), mime_type.to_owned());
+    if !DESERIALIZERS.with(|registered| registered.borrow_mut().insert(key)) {
+        return;
+    }
+
+    crate::content_register_deserializer::<(), _>(
+        mime_type,
+        T::static_type(),
+        |deserializer, _| {
+            let deserializer = deserializer.clone();
+            glib::MainContext::default().spawn_local(async move {
+                let output = gio::MemoryOutputStream::new_resizable();
+                let result = output
+                    .splice_future(
+                        &deserializer.input_stream(),
+                        gio::OutputStreamSpliceFlags::CLOSE_SOURCE
+                            | gio::OutputStreamSpliceFlags::CLOSE_TARGET,
+                        deserializer.priority(),
+                    )
+                    .await
+                    .and_then(|_| C::decode::<T>(&output.steal_as_bytes()).map_err(to_glib_error));
+                match result {
+                    Ok(value) => {
+                        deserializer.set_value(value.to_value());
+                        deserializer.return_success();
+                    }
+                    Err(err) => deserializer.return_error(err),
+                }
+            });
+        },
+    );
+}
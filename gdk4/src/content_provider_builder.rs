@@ -0,0 +1,318 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use crate::subclass::prelude::ObjectSubclassExt;
+use crate::{ContentProvider, FileList, Texture};
+use glib::translate::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+type WriteFunc = Rc<
+    dyn Fn(gio::OutputStream) -> Pin<Box<dyn Future<Output = Result<(), glib::Error>> + 'static>>,
+>;
+type ValueFunc = Rc<dyn Fn() -> glib::Value>;
+
+#[derive(Clone)]
+enum Format {
+    Mime(String, WriteFunc),
+    Value(glib::Type, ValueFunc),
+}
+
+mod imp {
+    use super::*;
+    use crate::subclass::prelude::*;
+    use crate::ContentFormats;
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    pub struct BuiltContentProvider {
+        pub(super) formats: RefCell<Vec<Format>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for BuiltContentProvider {
+        const NAME: &'static str = "GdkRsBuiltContentProvider";
+        type Type = super::BuiltContentProvider;
+        type ParentType = ContentProvider;
+    }
+
+    impl ObjectImpl for BuiltContentProvider {}
+
+    impl ContentProviderImpl for BuiltContentProvider {
+        fn formats(&self, _provider: &Self::Type) -> ContentFormats {
+            let builder =
+                self.formats
+                    .borrow()
+                    .iter()
+                    .fold(ContentFormats::builder(), |builder, format| match format {
+                        Format::Mime(mime_type, _) => builder.add_mime_type(mime_type),
+                        Format::Value(type_, _) => builder.add_type(*type_),
+                    });
+            let formats = builder.build();
+            formats.union_serialize_mime_types().unwrap_or(formats)
+        }
+
+        fn write_mime_type_future(
+            &self,
+            _provider: &Self::Type,
+            mime_type: &str,
+            stream: &gio::OutputStream,
+            io_priority: glib::Priority,
+        ) -> Pin<Box<dyn Future<Output = Result<(), glib::Error>> + 'static>> {
+            // Streams registered for the MIME type itself take precedence over
+            // serializing a value.
+            let formats = self.formats.borrow();
+            let write = formats.iter().find_map(|format| match format {
+                Format::Mime(mime, write) if mime == mime_type => Some(write.clone()),
+                _ => None,
+            });
+            if let Some(write) = write {
+                return write(stream.clone());
+            }
+            let value = formats.iter().find_map(|format| match format {
+                Format::Value(type_, value)
+                    if ContentFormats::for_type(*type_)
+                        .union_serialize_mime_types()
+                        .map_or(false, |formats| formats.contain_mime_type(mime_type)) =>
+                {
+                    Some(value())
+                }
+                _ => None,
+            });
+            match value {
+                Some(value) => {
+                    crate::content_serialize_future(stream, mime_type, &value, io_priority)
+                }
+                None => {
+                    let err = not_supported(mime_type);
+                    Box::pin(async move { Err(err) })
+                }
+            }
+        }
+
+        fn value(
+            &self,
+            _provider: &Self::Type,
+            type_: glib::Type,
+        ) -> Result<glib::Value, glib::Error> {
+            self.formats
+                .borrow()
+                .iter()
+                .find_map(|format| match format {
+                    Format::Value(value_type, value) if value_type.is_a(type_) => Some(value()),
+                    _ => None,
+                })
+                .ok_or_else(|| not_supported(type_.name()))
+        }
+    }
+
+    fn not_supported(format: &str) -> glib::Error {
+        glib::Error::new(
+            gio::IOErrorEnum::NotSupported,
+            &format!("Cannot provide contents as “{}”", format),
+        )
+    }
+}
+
+glib::wrapper! {
+    struct BuiltContentProvider(ObjectSubclass<imp::BuiltContentProvider>) @extends ContentProvider;
+}
+
+// rustdoc-stripper-ignore-next
+/// A builder for a [`ContentProvider`](crate::ContentProvider) offering the
+/// same content in several formats.
+///
+/// Every format is produced lazily, only when it is requested. Formats are
+/// preferred in the order they are added, and values like text, textures and
+/// files are also offered in every MIME type GDK knows to serialize them to.
+///
+/// ```no_run
+/// use gdk4 as gdk;
+/// use gdk::prelude::*;
+///
+/// # fn example(clipboard: &gdk::Clipboard, texture: gdk::Texture) {
+/// let provider = gdk::ContentProviderBuilder::new()
+///     .mime("text/html", |stream| async move {
+///         stream
+///             .write_all_future(b"<b>Hello</b>".to_vec(), gdk::glib::PRIORITY_DEFAULT)
+///             .await
+///             .map(|_| ())
+///             .map_err(|(_, err)| err)
+///     })
+///     .text(|| "Hello".to_owned())
+///     .texture(move || texture.clone())
+///     .build();
+/// clipboard.set_content(Some(&provider)).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Default)]
+#[must_use = "The builder must be built to be used"]
+pub struct ContentProviderBuilder {
+    formats: Vec<Format>,
+}
+
+impl ContentProviderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Offers the data written by `write` to the stream of a request for
+    /// `mime_type`.
+    pub fn mime<F, R>(mut self, mime_type: &str, write: F) -> Self
+    where
+        F: Fn(gio::OutputStream) -> R + 'static,
+        R: Future<Output = Result<(), glib::Error>> + 'static,
+    {
+        self.formats.push(Format::Mime(
+            mime_type.to_owned(),
+            Rc::new(move |stream| Box::pin(write(stream))),
+        ));
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Offers the value returned by `value`, both as a [`glib::Value`] of type
+    /// `T` and in the MIME types GDK can serialize `T` to.
+    pub fn value<T, F>(mut self, value: F) -> Self
+    where
+        T: ToValue + StaticType,
+        F: Fn() -> T + 'static,
+    {
+        self.formats.push(Format::Value(
+            T::static_type(),
+            Rc::new(move || value().to_value()),
+        ));
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Offers text, as a string and as `text/plain`.
+    pub fn text<F: Fn() -> String + 'static>(self, text: F) -> Self {
+        self.value(text)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Offers an image, as a [`Texture`](crate::Texture) and in image MIME
+    /// types like `image/png`.
+    pub fn texture<F: Fn() -> Texture + 'static>(self, texture: F) -> Self {
+        self.value(texture)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Offers a list of files, as a [`FileList`](crate::FileList) and as
+    /// `text/uri-list`.
+    pub fn files<F: Fn() -> Vec<gio::File> + 'static>(self, files: F) -> Self {
+        self.value(move || file_list(&files()))
+    }
+
+    pub fn build(self) -> ContentProvider {
+        let provider = glib::Object::new::<BuiltContentProvider>(&[])
+            .expect("Failed to create content provider");
+        imp::BuiltContentProvider::from_instance(&provider)
+            .formats
+            .replace(self.formats);
+        provider.upcast()
+    }
+}
+
+fn file_list(files: &[gio::File]) -> FileList {
+    // GDK has no constructor for file lists before 4.8, but the boxed type is
+    // a plain GSList of files.
+    unsafe {
+        let list = files.iter().rev().fold(std::ptr::null_mut(), |list, file| {
+            glib::ffi::g_slist_prepend(list, file.to_glib_full() as glib::ffi::gpointer)
+        });
+        from_glib_full(list as *mut ffi::GdkFileList)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UTF8: &str = "text/plain;charset=utf-8";
+
+    fn write(
+        data: &'static str,
+    ) -> impl Fn(gio::OutputStream) -> Pin<Box<dyn Future<Output = Result<(), glib::Error>>>> {
+        move |stream| {
+            Box::pin(async move {
+                stream
+                    .write_all_future(data.as_bytes().to_vec(), glib::PRIORITY_DEFAULT)
+                    .await
+                    .map(|_| ())
+                    .map_err(|(_, err)| err)
+            })
+        }
+    }
+
+    fn read(provider: &ContentProvider, mime_type: &str) -> Result<String, glib::Error> {
+        let stream = gio::MemoryOutputStream::new_resizable();
+        glib::MainContext::new().block_on(provider.write_mime_type_future(
+            mime_type,
+            &stream,
+            glib::PRIORITY_DEFAULT,
+        ))?;
+        stream.close(gio::Cancellable::NONE)?;
+        Ok(String::from_utf8(stream.steal_as_bytes().to_vec()).unwrap())
+    }
+
+    #[test]
+    fn test_formats_order() {
+        let provider = ContentProviderBuilder::new()
+            .mime("text/html", write("<b>html</b>"))
+            .text(|| "text".to_owned())
+            .mime("application/x-test", write("test"))
+            .build();
+        let formats = provider.formats();
+        assert_eq!(formats.types(), [glib::Type::STRING]);
+
+        // MIME types added to the builder come first, in order, followed by the
+        // ones the values are serialized to.
+        let mime_types = formats
+            .mime_types()
+            .iter()
+            .map(|mime_type| mime_type.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(mime_types[..2], ["text/html", "application/x-test"]);
+        assert!(mime_types[2..].iter().any(|mime_type| mime_type == UTF8));
+    }
+
+    #[test]
+    fn test_mime_precedence() {
+        let provider = ContentProviderBuilder::new()
+            .text(|| "value".to_owned())
+            .build();
+        assert_eq!(read(&provider, UTF8).unwrap(), "value");
+
+        let provider = ContentProviderBuilder::new()
+            .text(|| "value".to_owned())
+            .mime(UTF8, write("stream"))
+            .build();
+        assert_eq!(read(&provider, UTF8).unwrap(), "stream");
+        assert_eq!(
+            provider
+                .value(glib::Type::STRING)
+                .unwrap()
+                .get::<String>()
+                .unwrap(),
+            "value"
+        );
+    }
+
+    #[test]
+    fn test_unsupported() {
+        let provider = ContentProviderBuilder::new()
+            .mime("text/html", write("<b>html</b>"))
+            .build();
+        assert!(read(&provider, UTF8)
+            .unwrap_err()
+            .matches(gio::IOErrorEnum::NotSupported));
+        assert!(provider
+            .value(glib::Type::STRING)
+            .unwrap_err()
+            .matches(gio::IOErrorEnum::NotSupported));
+    }
+}
@@ -0,0 +1,250 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use crate::{keys::Key, Display, KeyEvent};
+
+// Keycodes of physical keys, which are in the range of XKB keycodes on every
+// backend.
+const KEYCODES: std::ops::RangeInclusive<u32> = 8..=255;
+
+fn is_latin(key: Key) -> bool {
+    key.to_unicode().map_or(false, |c| c.is_ascii_graphic())
+}
+
+// rustdoc-stripper-ignore-next
+/// A physical key of a keyboard, and the keys it produces in every layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhysicalKey {
+    keycode: u32,
+    // Indexed by group, then by level.
+    keyvals: Vec<Vec<Option<Key>>>,
+}
+
+impl PhysicalKey {
+    pub fn keycode(&self) -> u32 {
+        self.keycode
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The number of layouts in which the key produces something.
+    pub fn n_groups(&self) -> u32 {
+        self.keyvals.len() as u32
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The key produced in layout `group` at shift `level`, where level 0 is
+    /// the key without modifiers and level 1 with Shift.
+    pub fn keyval(&self, group: u32, level: u32) -> Option<Key> {
+        *self.keyvals.get(group as usize)?.get(level as usize)?
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The keys produced in layout `group`, by level.
+    pub fn levels(&self, group: u32) -> Vec<Option<Key>> {
+        self.keyvals
+            .get(group as usize)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The key to match shortcuts against when this key is pressed in layout
+    /// `group`.
+    ///
+    /// That is the key of `group` when it's a Latin character, so `Ctrl+Z`
+    /// follows the label of the key on AZERTY or Dvorak layouts. On non-Latin
+    /// layouts, like Cyrillic ones, it's the key of the first layout producing
+    /// a Latin character instead, so shortcuts keep working without switching
+    /// layouts.
+    pub fn shortcut_key(&self, group: u32) -> Option<Key> {
+        let key = self.keyval(group, 0);
+        if key.map_or(false, is_latin) {
+            return key;
+        }
+        (0..self.n_groups())
+            .filter_map(|group| self.keyval(group, 0))
+            .find(|key| is_latin(*key))
+            .or(key)
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// The keyboard layouts of a display, by physical key.
+///
+/// Layouts are known to GDK as groups, numbered from 0. Key events tell which
+/// group was active when they were emitted, with
+/// [`KeyEvent::layout`](crate::KeyEvent::layout), and
+/// [`active_group`](Self::active_group) tells which one is active now.
+///
+/// ```no_run
+/// use gdk4 as gdk;
+///
+/// # fn example(display: &gdk::Display, event: &gdk::KeyEvent) {
+/// let layout = gdk::KeyboardLayout::new(display);
+/// if layout.shortcut_key_for_event(event) == Some(gdk::keys::constants::z)
+///     && event.modifier_state().contains(gdk::ModifierType::CONTROL_MASK)
+/// {
+///     // Undo, wherever Z is on the keyboard.
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct KeyboardLayout {
+    display: Display,
+}
+
+impl KeyboardLayout {
+    pub fn new(display: &impl IsA<Display>) -> Self {
+        skip_assert_initialized!();
+        Self {
+            display: display.as_ref().clone(),
+        }
+    }
+
+    pub fn display(&self) -> &Display {
+        &self.display
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The physical key with `keycode`, or `None` if it doesn't produce
+    /// anything in any layout.
+    pub fn key(&self, keycode: u32) -> Option<PhysicalKey> {
+        let entries = self.display.map_keycode(keycode)?;
+        let mut keyvals: Vec<Vec<Option<Key>>> = Vec::new();
+        for (entry, keyval) in entries {
+            let (group, level) = (entry.group().max(0) as usize, entry.level().max(0) as usize);
+            if keyvals.len() <= group {
+                keyvals.resize(group + 1, Vec::new());
+            }
+            if keyvals[group].len() <= level {
+                keyvals[group].resize(level + 1, None);
+            }
+            keyvals[group][level] = Some(keyval);
+        }
+        if keyvals.is_empty() {
+            None
+        } else {
+            Some(PhysicalKey { keycode, keyvals })
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// All physical keys of the keyboard.
+    pub fn keys(&self) -> impl Iterator<Item = PhysicalKey> + '_ {
+        KEYCODES.filter_map(move |keycode| self.key(keycode))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The number of layouts, the largest number of groups of any key.
+    pub fn n_groups(&self) -> u32 {
+        self.keys().map(|key| key.n_groups()).max().unwrap_or(0)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The layout currently active on the keyboard of the default seat.
+    ///
+    /// GTK reports it since version 4.14, with the `active-layout-index`
+    /// property of the keyboard device. With older versions, or when the
+    /// backend doesn't know, this returns `None` and the active layout is only
+    /// known from key events.
+    pub fn active_group(&self) -> Option<u32> {
+        let keyboard = self.display.default_seat().keyboard()?;
+        keyboard.find_property("active-layout-index")?;
+        u32::try_from(keyboard.property::<i32>("active-layout-index")).ok()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The keycodes of the physical keys producing `keyval`, in any layout.
+    pub fn keycodes(&self, keyval: Key) -> Vec<u32> {
+        let mut keycodes: Vec<u32> = self
+            .display
+            .map_keyval(keyval)
+            .unwrap_or_default()
+            .iter()
+            .map(|key| key.keycode())
+            .collect();
+        keycodes.sort_unstable();
+        keycodes.dedup();
+        keycodes
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The key to match shortcuts against when the key with `keycode` is
+    /// pressed in layout `group`. See [`PhysicalKey::shortcut_key`].
+    pub fn shortcut_key(&self, keycode: u32, group: u32) -> Option<Key> {
+        self.key(keycode)?.shortcut_key(group)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The key to match shortcuts against for a key press.
+    pub fn shortcut_key_for_event(&self, event: &KeyEvent) -> Option<Key> {
+        self.shortcut_key(event.keycode(), event.layout())
+    }
+}
+
+impl Key {
+    // rustdoc-stripper-ignore-next
+    /// The key to match shortcuts against when the key with `keycode` is
+    /// pressed on the default display, in the active layout.
+    ///
+    /// See [`PhysicalKey::shortcut_key`] for how the key is chosen. When the
+    /// active layout is unknown, see [`KeyboardLayout::active_group`], the
+    /// first layout is used. Prefer
+    /// [`KeyboardLayout::shortcut_key_for_event`] when handling a key event,
+    /// which knows the layout of the event.
+    pub fn from_physical(keycode: u32) -> Option<Self> {
+        assert_initialized_main_thread!();
+        let layout = KeyboardLayout::new(&Display::default()?);
+        let group = layout.active_group().unwrap_or(0);
+        layout.shortcut_key(keycode, group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::constants;
+
+    fn key(groups: &[&[Key]]) -> PhysicalKey {
+        PhysicalKey {
+            keycode: 24,
+            keyvals: groups
+                .iter()
+                .map(|levels| levels.iter().copied().map(Some).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_shortcut_key_latin() {
+        // The key labeled Q on QWERTY is A on AZERTY.
+        let q = key(&[&[constants::q, constants::Q], &[constants::a, constants::A]]);
+        assert_eq!(q.n_groups(), 2);
+        assert_eq!(q.keyval(1, 1), Some(constants::A));
+        assert_eq!(q.shortcut_key(0), Some(constants::q));
+        assert_eq!(q.shortcut_key(1), Some(constants::a));
+
+        // The Z of Dvorak is on the key of the slash of QWERTY, whose Z key
+        // produces a semicolon.
+        let slash = key(&[&[constants::slash], &[constants::z]]);
+        assert_eq!(slash.shortcut_key(1), Some(constants::z));
+        let z = key(&[&[constants::z], &[constants::semicolon]]);
+        assert_eq!(z.shortcut_key(1), Some(constants::semicolon));
+    }
+
+    #[test]
+    fn test_shortcut_key_non_latin() {
+        // Cyrillic layouts use the key of the first Latin layout.
+        let q = key(&[&[constants::Cyrillic_shorti], &[constants::q]]);
+        assert_eq!(q.shortcut_key(0), Some(constants::q));
+        assert_eq!(q.shortcut_key(1), Some(constants::q));
+
+        // Unless there is none.
+        let ya = key(&[&[constants::Cyrillic_ya, constants::Cyrillic_YA]]);
+        assert_eq!(ya.shortcut_key(0), Some(constants::Cyrillic_ya));
+
+        // Groups the key doesn't have fall back to the Latin ones.
+        assert_eq!(q.shortcut_key(5), Some(constants::q));
+        assert_eq!(q.keyval(5, 0), None);
+    }
+}
@@ -11,6 +11,7 @@ pub use ffi;
 pub use gdk_pixbuf;
 pub use gio;
 pub use glib;
+pub use graphene;
 pub use pango;
 
 #[doc(alias = "GDK_PRIORITY_REDRAW")]
@@ -62,13 +63,19 @@ pub mod builders;
 pub mod prelude;
 pub mod subclass;
 
+mod accelerator;
+mod animated_paintable;
 mod button_event;
 mod cairo_interaction;
 mod clipboard;
+mod color_space;
+#[cfg(feature = "serde")]
+mod content_codec;
 mod content_deserializer;
 mod content_formats;
 mod content_formats_builder;
 mod content_provider;
+mod content_provider_builder;
 mod content_serializer;
 mod crossing_event;
 mod delete_event;
@@ -81,6 +88,7 @@ mod functions;
 mod gl_texture;
 mod grab_broken_event;
 mod key_event;
+mod keyboard_layout;
 mod keymap_key;
 pub mod keys;
 mod motion_event;
@@ -88,10 +96,12 @@ mod pad_event;
 mod popup_layout;
 mod proximity_event;
 mod rectangle;
+mod region;
 mod rgba;
 mod scroll_event;
 mod surface;
 mod texture;
+mod texture_data;
 mod time_coord;
 mod toplevel;
 mod toplevel_size;
@@ -103,7 +113,19 @@ pub use auto::*;
 
 pub use functions::*;
 
+pub use accelerator::Accelerator;
+pub use animated_paintable::AnimatedPaintable;
 pub use button_event::ButtonEvent;
+pub use color_space::{Hsl, Hsv, LinearRgb, OkLab, OkLch};
+#[cfg(feature = "json")]
+#[cfg_attr(feature = "dox", doc(cfg(feature = "json")))]
+pub use content_codec::JsonCodec;
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "dox", doc(cfg(feature = "serde")))]
+pub use content_codec::{
+    content_register_serde_deserializer, content_register_serde_serializer, ContentCodec,
+};
+pub use content_provider_builder::ContentProviderBuilder;
 pub use crossing_event::CrossingEvent;
 pub use delete_event::DeleteEvent;
 pub use display::Backend;
@@ -112,11 +134,15 @@ pub use event::Event;
 pub use focus_event::FocusEvent;
 pub use grab_broken_event::GrabBrokenEvent;
 pub use key_event::KeyEvent;
+pub use keyboard_layout::{KeyboardLayout, PhysicalKey};
 pub use keymap_key::KeymapKey;
 pub use motion_event::MotionEvent;
 pub use pad_event::PadEvent;
 pub use proximity_event::ProximityEvent;
+pub use rectangle::RoundingMode;
+pub use region::{Region, RegionIter};
 pub use scroll_event::ScrollEvent;
+pub use texture_data::TextureData;
 pub use time_coord::TimeCoord;
 pub use toplevel_size::ToplevelSize;
 pub use touch_event::TouchEvent;
@@ -31,6 +31,140 @@ impl Rectangle {
     pub fn height(&self) -> i32 {
         self.0.height
     }
+
+    // rustdoc-stripper-ignore-next
+    /// Whether the rectangle has no area.
+    pub fn is_empty(&self) -> bool {
+        self.width() <= 0 || self.height() <= 0
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The center of the rectangle, which is in between pixels when the width
+    /// or height is odd.
+    pub fn center(&self) -> (f64, f64) {
+        (
+            self.x() as f64 + self.width() as f64 / 2.0,
+            self.y() as f64 + self.height() as f64 / 2.0,
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Whether `other` is entirely inside the rectangle. Empty rectangles are
+    /// inside every rectangle.
+    pub fn contains_rect(&self, other: &Rectangle) -> bool {
+        other.is_empty()
+            || (other.x() >= self.x()
+                && other.y() >= self.y()
+                && right(other) <= right(self)
+                && bottom(other) <= bottom(self))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The rectangle moved by `dx` horizontally and `dy` vertically.
+    ///
+    /// Coordinates saturate at the bounds of `i32` instead of overflowing.
+    pub fn translate(&self, dx: i32, dy: i32) -> Rectangle {
+        Rectangle::new(
+            self.x().saturating_add(dx),
+            self.y().saturating_add(dy),
+            self.width(),
+            self.height(),
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The rectangle shrunk by `dx` on the left and right sides and by `dy`
+    /// on the top and bottom sides.
+    ///
+    /// The size doesn't go below zero, so insetting a rectangle by more than
+    /// half its size gives an empty rectangle. Coordinates and sizes saturate
+    /// at the bounds of `i32` instead of overflowing.
+    pub fn inset(&self, dx: i32, dy: i32) -> Rectangle {
+        Rectangle::new(
+            self.x().saturating_add(dx),
+            self.y().saturating_add(dy),
+            self.width().saturating_sub(dx.saturating_mul(2)).max(0),
+            self.height().saturating_sub(dy.saturating_mul(2)).max(0),
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The rectangle grown by `dx` on the left and right sides and by `dy` on
+    /// the top and bottom sides.
+    pub fn outset(&self, dx: i32, dy: i32) -> Rectangle {
+        self.inset(dx.saturating_neg(), dy.saturating_neg())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The rectangle with its coordinates multiplied by `factor`, rounded to
+    /// whole pixels with `mode`.
+    ///
+    /// This converts between logical and device pixels, where `factor` is a
+    /// scale factor of a surface or its inverse.
+    pub fn scale(&self, factor: f64, mode: RoundingMode) -> Rectangle {
+        round(
+            self.x() as f64 * factor,
+            self.y() as f64 * factor,
+            right(self) as f64 * factor,
+            bottom(self) as f64 * factor,
+            mode,
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The rectangle of whole pixels corresponding to `rect`, rounded with
+    /// `mode`.
+    pub fn from_graphene(rect: &graphene::Rect, mode: RoundingMode) -> Rectangle {
+        skip_assert_initialized!();
+        let (x, y) = (rect.x() as f64, rect.y() as f64);
+        round(
+            x,
+            y,
+            x + rect.width() as f64,
+            y + rect.height() as f64,
+            mode,
+        )
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// How fractional coordinates are rounded to whole pixels, in
+/// [`Rectangle::scale`] and [`Rectangle::from_graphene`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    // rustdoc-stripper-ignore-next
+    /// Every edge is rounded to the nearest pixel.
+    Nearest,
+    // rustdoc-stripper-ignore-next
+    /// The smallest rectangle containing every pixel touched by the
+    /// fractional one, as needed for damage.
+    Outward,
+    // rustdoc-stripper-ignore-next
+    /// The largest rectangle of pixels fully covered by the fractional one, as
+    /// needed for opaque regions.
+    Inward,
+}
+
+fn right(rect: &Rectangle) -> i64 {
+    rect.x() as i64 + rect.width() as i64
+}
+
+fn bottom(rect: &Rectangle) -> i64 {
+    rect.y() as i64 + rect.height() as i64
+}
+
+fn round(x1: f64, y1: f64, x2: f64, y2: f64, mode: RoundingMode) -> Rectangle {
+    let (x1, y1, x2, y2) = match mode {
+        RoundingMode::Nearest => (x1.round(), y1.round(), x2.round(), y2.round()),
+        RoundingMode::Outward => (x1.floor(), y1.floor(), x2.ceil(), y2.ceil()),
+        RoundingMode::Inward => (x1.ceil(), y1.ceil(), x2.floor(), y2.floor()),
+    };
+    Rectangle::new(
+        x1 as i32,
+        y1 as i32,
+        (x2 - x1).max(0.0) as i32,
+        (y2 - y1).max(0.0) as i32,
+    )
 }
 
 impl fmt::Debug for Rectangle {
@@ -56,3 +190,125 @@ impl From<RectangleInt> for Rectangle {
         unsafe { *(&r as *const _ as *const _) }
     }
 }
+
+impl From<Rectangle> for RectangleInt {
+    fn from(r: Rectangle) -> RectangleInt {
+        skip_assert_initialized!();
+        *r.as_ref()
+    }
+}
+
+impl From<Rectangle> for graphene::Rect {
+    fn from(r: Rectangle) -> graphene::Rect {
+        skip_assert_initialized!();
+        graphene::Rect::new(
+            r.x() as f32,
+            r.y() as f32,
+            r.width() as f32,
+            r.height() as f32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_inset() {
+        let rect = Rectangle::new(0, 0, 10, 6);
+        assert_eq!(rect.translate(3, -2), Rectangle::new(3, -2, 10, 6));
+        assert_eq!(rect.inset(2, 1), Rectangle::new(2, 1, 6, 4));
+        assert_eq!(rect.outset(2, 1), Rectangle::new(-2, -1, 14, 8));
+        assert!(rect.inset(6, 0).is_empty());
+        assert_eq!(rect.inset(6, 0).width(), 0);
+    }
+
+    #[test]
+    fn test_saturation() {
+        let rect = Rectangle::new(i32::MAX - 1, i32::MIN + 1, 10, 10);
+        assert_eq!(
+            rect.translate(5, -5),
+            Rectangle::new(i32::MAX, i32::MIN, 10, 10)
+        );
+
+        let rect = Rectangle::new(0, 0, 10, 10);
+        assert_eq!(
+            rect.inset(i32::MAX, i32::MIN),
+            Rectangle::new(i32::MAX, i32::MIN, 0, i32::MAX)
+        );
+        assert_eq!(
+            rect.outset(i32::MAX, i32::MIN),
+            Rectangle::new(-i32::MAX, i32::MAX, i32::MAX, 0)
+        );
+        assert_eq!(
+            Rectangle::new(0, 0, i32::MAX, 1).scale(4.0, RoundingMode::Nearest),
+            Rectangle::new(0, 0, i32::MAX, 4)
+        );
+    }
+
+    #[test]
+    fn test_scale() {
+        let rect = Rectangle::new(1, 1, 4, 4);
+        // The edges at 0.5 and 2.5 round in each mode.
+        assert_eq!(
+            rect.scale(0.5, RoundingMode::Nearest),
+            Rectangle::new(1, 1, 2, 2)
+        );
+        assert_eq!(
+            rect.scale(0.5, RoundingMode::Outward),
+            Rectangle::new(0, 0, 3, 3)
+        );
+        assert_eq!(
+            rect.scale(0.5, RoundingMode::Inward),
+            Rectangle::new(1, 1, 1, 1)
+        );
+
+        // Whole factors are exact in every mode.
+        for mode in [
+            RoundingMode::Nearest,
+            RoundingMode::Outward,
+            RoundingMode::Inward,
+        ] {
+            assert_eq!(rect.scale(2.0, mode), Rectangle::new(2, 2, 8, 8));
+        }
+    }
+
+    #[test]
+    fn test_from_graphene() {
+        let rect = graphene::Rect::new(0.25, 0.75, 1.5, 0.5);
+        assert_eq!(
+            Rectangle::from_graphene(&rect, RoundingMode::Nearest),
+            Rectangle::new(0, 1, 2, 0)
+        );
+        assert_eq!(
+            Rectangle::from_graphene(&rect, RoundingMode::Outward),
+            Rectangle::new(0, 0, 2, 2)
+        );
+        assert_eq!(
+            Rectangle::from_graphene(&rect, RoundingMode::Inward),
+            Rectangle::new(1, 1, 0, 0)
+        );
+
+        // Negative coordinates round towards the same sides.
+        let rect = graphene::Rect::new(-1.5, -0.5, 1.0, 1.0);
+        assert_eq!(
+            Rectangle::from_graphene(&rect, RoundingMode::Nearest),
+            Rectangle::new(-2, -1, 1, 2)
+        );
+        assert_eq!(
+            Rectangle::from_graphene(&rect, RoundingMode::Outward),
+            Rectangle::new(-2, -1, 2, 2)
+        );
+        assert_eq!(
+            Rectangle::from_graphene(&rect, RoundingMode::Inward),
+            Rectangle::new(-1, 0, 0, 0)
+        );
+
+        let rect = Rectangle::new(-3, 4, 5, 6);
+        assert_eq!(
+            Rectangle::from_graphene(&graphene::Rect::from(rect), RoundingMode::Inward),
+            rect
+        );
+    }
+}
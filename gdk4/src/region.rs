@@ -0,0 +1,408 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::Rectangle;
+use std::iter::FromIterator;
+
+// A horizontal band of the region, covering the rows from `y1` to `y2` with
+// the same spans of columns.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Band {
+    y1: i32,
+    y2: i32,
+    // Sorted, non-empty spans from `x1` to `x2`, which don't touch each other.
+    spans: Vec<(i32, i32)>,
+}
+
+fn covers(spans: &[(i32, i32)], x: i32) -> bool {
+    let i = spans.partition_point(|(_, x2)| *x2 <= x);
+    spans.get(i).map_or(false, |(x1, _)| *x1 <= x)
+}
+
+fn band_at(bands: &[Band], y: i32) -> &[(i32, i32)] {
+    let i = bands.partition_point(|band| band.y2 <= y);
+    match bands.get(i) {
+        Some(band) if band.y1 <= y => &band.spans,
+        _ => &[],
+    }
+}
+
+fn combine_spans(
+    a: &[(i32, i32)],
+    b: &[(i32, i32)],
+    op: fn(bool, bool) -> bool,
+) -> Vec<(i32, i32)> {
+    let mut xs: Vec<i32> = a.iter().chain(b).flat_map(|(x1, x2)| [*x1, *x2]).collect();
+    xs.sort_unstable();
+    xs.dedup();
+
+    let mut spans: Vec<(i32, i32)> = Vec::new();
+    for edges in xs.windows(2) {
+        let (x1, x2) = (edges[0], edges[1]);
+        if !op(covers(a, x1), covers(b, x1)) {
+            continue;
+        }
+        match spans.last_mut() {
+            Some(last) if last.1 == x1 => last.1 = x2,
+            _ => spans.push((x1, x2)),
+        }
+    }
+    spans
+}
+
+// Combines two regions row by row: a pixel is in the result when `op` is true
+// for whether it's in `a` and in `b`.
+fn combine(a: &[Band], b: &[Band], op: fn(bool, bool) -> bool) -> Vec<Band> {
+    let mut ys: Vec<i32> = a
+        .iter()
+        .chain(b)
+        .flat_map(|band| [band.y1, band.y2])
+        .collect();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let mut bands: Vec<Band> = Vec::new();
+    for edges in ys.windows(2) {
+        let (y1, y2) = (edges[0], edges[1]);
+        let spans = combine_spans(band_at(a, y1), band_at(b, y1), op);
+        if spans.is_empty() {
+            continue;
+        }
+        match bands.last_mut() {
+            Some(last) if last.y2 == y1 && last.spans == spans => last.y2 = y2,
+            _ => bands.push(Band { y1, y2, spans }),
+        }
+    }
+    bands
+}
+
+fn bands_of(rect: &Rectangle) -> Vec<Band> {
+    if rect.is_empty() {
+        return Vec::new();
+    }
+    vec![Band {
+        y1: rect.y(),
+        y2: rect.y() + rect.height(),
+        spans: vec![(rect.x(), rect.x() + rect.width())],
+    }]
+}
+
+// rustdoc-stripper-ignore-next
+/// A set of pixels, made of rectangles.
+///
+/// This is a plain Rust value, like [`cairo::Region`] but without reference
+/// counting or allocations in C, meant for damage tracking in widgets. Regions
+/// are kept in a canonical form of horizontal bands, so two regions covering
+/// the same pixels are equal and are split in the same rectangles.
+///
+/// ```
+/// use gdk4 as gdk;
+///
+/// let mut damage = gdk::Region::from(gdk::Rectangle::new(0, 0, 100, 100));
+/// damage.subtract_rect(&gdk::Rectangle::new(25, 25, 50, 50));
+/// assert_eq!(damage.n_rects(), 4);
+/// assert!(!damage.contains_point(50, 50));
+/// assert_eq!(damage.extents(), gdk::Rectangle::new(0, 0, 100, 100));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Region {
+    bands: Vec<Band>,
+}
+
+impl Region {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bands.is_empty()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The smallest rectangle containing the region, which is empty for an
+    /// empty region.
+    pub fn extents(&self) -> Rectangle {
+        let (first, last) = match (self.bands.first(), self.bands.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Rectangle::new(0, 0, 0, 0),
+        };
+        let x1 = self.bands.iter().map(|band| band.spans[0].0).min().unwrap();
+        let x2 = self
+            .bands
+            .iter()
+            .map(|band| band.spans[band.spans.len() - 1].1)
+            .max()
+            .unwrap();
+        Rectangle::new(x1, first.y1, x2 - x1, last.y2 - first.y1)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The number of rectangles returned by [`Self::iter`].
+    pub fn n_rects(&self) -> usize {
+        self.bands.iter().map(|band| band.spans.len()).sum()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The rectangles making up the region, which don't overlap, from top to
+    /// bottom and left to right.
+    pub fn iter(&self) -> RegionIter<'_> {
+        RegionIter {
+            bands: self.bands.iter(),
+            band: None,
+        }
+    }
+
+    pub fn contains_point(&self, x: i32, y: i32) -> bool {
+        covers(band_at(&self.bands, y), x)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Whether every pixel of `rect` is in the region.
+    pub fn contains_rect(&self, rect: &Rectangle) -> bool {
+        combine(&bands_of(rect), &self.bands, |a, b| a && !b).is_empty()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Whether some pixel of `rect` is in the region.
+    pub fn intersects_rect(&self, rect: &Rectangle) -> bool {
+        !combine(&bands_of(rect), &self.bands, |a, b| a && b).is_empty()
+    }
+
+    pub fn translate(&mut self, dx: i32, dy: i32) {
+        for band in &mut self.bands {
+            band.y1 += dy;
+            band.y2 += dy;
+            for span in &mut band.spans {
+                span.0 += dx;
+                span.1 += dx;
+            }
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Adds the pixels of `other` to the region.
+    pub fn union(&mut self, other: &Region) {
+        self.bands = combine(&self.bands, &other.bands, |a, b| a || b);
+    }
+
+    pub fn union_rect(&mut self, rect: &Rectangle) {
+        self.bands = combine(&self.bands, &bands_of(rect), |a, b| a || b);
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Keeps only the pixels of the region that are also in `other`.
+    pub fn intersect(&mut self, other: &Region) {
+        self.bands = combine(&self.bands, &other.bands, |a, b| a && b);
+    }
+
+    pub fn intersect_rect(&mut self, rect: &Rectangle) {
+        self.bands = combine(&self.bands, &bands_of(rect), |a, b| a && b);
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Removes the pixels of `other` from the region.
+    pub fn subtract(&mut self, other: &Region) {
+        self.bands = combine(&self.bands, &other.bands, |a, b| a && !b);
+    }
+
+    pub fn subtract_rect(&mut self, rect: &Rectangle) {
+        self.bands = combine(&self.bands, &bands_of(rect), |a, b| a && !b);
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Keeps the pixels that are either in the region or in `other`, but not
+    /// in both.
+    pub fn xor(&mut self, other: &Region) {
+        self.bands = combine(&self.bands, &other.bands, |a, b| a != b);
+    }
+}
+
+impl From<Rectangle> for Region {
+    fn from(rect: Rectangle) -> Self {
+        skip_assert_initialized!();
+        Self {
+            bands: bands_of(&rect),
+        }
+    }
+}
+
+impl FromIterator<Rectangle> for Region {
+    fn from_iter<I: IntoIterator<Item = Rectangle>>(iter: I) -> Self {
+        let mut region = Self::new();
+        region.extend(iter);
+        region
+    }
+}
+
+impl Extend<Rectangle> for Region {
+    fn extend<I: IntoIterator<Item = Rectangle>>(&mut self, iter: I) {
+        for rect in iter {
+            self.union_rect(&rect);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Region {
+    type Item = Rectangle;
+    type IntoIter = RegionIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl From<&Region> for cairo::Region {
+    fn from(region: &Region) -> cairo::Region {
+        skip_assert_initialized!();
+        let rects: Vec<cairo::RectangleInt> = region.iter().map(Into::into).collect();
+        cairo::Region::create_rectangles(&rects)
+    }
+}
+
+impl From<&cairo::Region> for Region {
+    fn from(region: &cairo::Region) -> Self {
+        skip_assert_initialized!();
+        (0..region.num_rectangles())
+            .map(|i| Rectangle::from(region.rectangle(i)))
+            .collect()
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// An iterator over the rectangles of a [`Region`].
+#[derive(Debug, Clone)]
+pub struct RegionIter<'a> {
+    bands: std::slice::Iter<'a, Band>,
+    band: Option<(&'a Band, std::slice::Iter<'a, (i32, i32)>)>,
+}
+
+impl<'a> Iterator for RegionIter<'a> {
+    type Item = Rectangle;
+
+    fn next(&mut self) -> Option<Rectangle> {
+        loop {
+            if let Some((band, spans)) = &mut self.band {
+                if let Some((x1, x2)) = spans.next() {
+                    return Some(Rectangle::new(*x1, band.y1, x2 - x1, band.y2 - band.y1));
+                }
+            }
+            let band = self.bands.next()?;
+            self.band = Some((band, band.spans.iter()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    fn rects(region: &Region) -> Vec<Rectangle> {
+        region.iter().collect()
+    }
+
+    fn hash(region: &Region) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        region.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_union() {
+        let mut region = Region::from(Rectangle::new(0, 0, 10, 10));
+        region.union_rect(&Rectangle::new(5, 5, 10, 10));
+        assert_eq!(
+            rects(&region),
+            [
+                Rectangle::new(0, 0, 10, 5),
+                Rectangle::new(0, 5, 15, 5),
+                Rectangle::new(5, 10, 10, 5),
+            ]
+        );
+        assert_eq!(region.extents(), Rectangle::new(0, 0, 15, 15));
+        assert!(region.contains_point(12, 7));
+        assert!(!region.contains_point(12, 2));
+
+        region.union_rect(&Rectangle::new(100, 100, 0, 10));
+        assert_eq!(region.n_rects(), 3);
+    }
+
+    #[test]
+    fn test_intersect() {
+        let mut region = Region::from(Rectangle::new(0, 0, 10, 10));
+        region.intersect(&Region::from(Rectangle::new(5, 5, 10, 10)));
+        assert_eq!(rects(&region), [Rectangle::new(5, 5, 5, 5)]);
+
+        region.intersect_rect(&Rectangle::new(20, 20, 5, 5));
+        assert!(region.is_empty());
+        assert_eq!(region.extents(), Rectangle::new(0, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_subtract() {
+        let mut region = Region::from(Rectangle::new(0, 0, 100, 100));
+        region.subtract_rect(&Rectangle::new(25, 25, 50, 50));
+        assert_eq!(
+            rects(&region),
+            [
+                Rectangle::new(0, 0, 100, 25),
+                Rectangle::new(0, 25, 25, 50),
+                Rectangle::new(75, 25, 25, 50),
+                Rectangle::new(0, 75, 100, 25),
+            ]
+        );
+        assert!(region.contains_rect(&Rectangle::new(0, 0, 100, 25)));
+        assert!(!region.contains_rect(&Rectangle::new(0, 0, 50, 50)));
+        assert!(region.intersects_rect(&Rectangle::new(0, 0, 50, 50)));
+        assert!(!region.intersects_rect(&Rectangle::new(30, 30, 10, 10)));
+
+        region.subtract(&region.clone());
+        assert!(region.is_empty());
+    }
+
+    #[test]
+    fn test_xor() {
+        let mut region = Region::from(Rectangle::new(0, 0, 10, 10));
+        region.xor(&Region::from(Rectangle::new(5, 0, 10, 10)));
+        assert_eq!(
+            rects(&region),
+            [Rectangle::new(0, 0, 5, 10), Rectangle::new(10, 0, 5, 10)]
+        );
+
+        region.xor(&Region::from(Rectangle::new(5, 0, 5, 10)));
+        assert_eq!(rects(&region), [Rectangle::new(0, 0, 15, 10)]);
+    }
+
+    #[test]
+    fn test_band_merging() {
+        let stacked: Region = [Rectangle::new(0, 0, 10, 5), Rectangle::new(0, 5, 10, 5)]
+            .into_iter()
+            .collect();
+        assert_eq!(rects(&stacked), [Rectangle::new(0, 0, 10, 10)]);
+
+        let side_by_side: Region = [Rectangle::new(0, 0, 5, 10), Rectangle::new(5, 0, 5, 10)]
+            .into_iter()
+            .collect();
+        assert_eq!(rects(&side_by_side), [Rectangle::new(0, 0, 10, 10)]);
+
+        let mut translated = stacked.clone();
+        translated.translate(5, -5);
+        assert_eq!(rects(&translated), [Rectangle::new(5, -5, 10, 10)]);
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let whole = Region::from(Rectangle::new(0, 0, 10, 10));
+        let rows: Region = (0..10).map(|y| Rectangle::new(0, y, 10, 1)).collect();
+        let columns: Region = (0..10).map(|x| Rectangle::new(x, 0, 1, 10)).collect();
+        let mut overlapping = Region::from(Rectangle::new(0, 0, 8, 8));
+        overlapping.extend([Rectangle::new(2, 2, 8, 8), Rectangle::new(0, 0, 10, 10)]);
+
+        for region in [&rows, &columns, &overlapping] {
+            assert_eq!(region, &whole);
+            assert_eq!(hash(region), hash(&whole));
+        }
+        assert_ne!(whole, Region::from(Rectangle::new(0, 0, 10, 9)));
+        assert_eq!(Region::new(), Region::from(Rectangle::new(5, 5, 0, 0)));
+    }
+}
@@ -130,6 +130,123 @@ impl RGBA {
         }
     }
 
+    // rustdoc-stripper-ignore-next
+    /// Interpolates between `self` at `0.0` and `other` at `1.0`, with
+    /// premultiplied alpha so that transparent colors don't tint the result.
+    pub fn mix(&self, other: &RGBA, progress: f32) -> RGBA {
+        let alpha = self.alpha() + (other.alpha() - self.alpha()) * progress;
+        if alpha <= 0.0 {
+            return RGBA::new(0.0, 0.0, 0.0, 0.0);
+        }
+        let mix = |a: f32, b: f32| {
+            let a = a * self.alpha();
+            let b = b * other.alpha();
+            (a + (b - a) * progress) / alpha
+        };
+        RGBA::new(
+            mix(self.red(), other.red()),
+            mix(self.green(), other.green()),
+            mix(self.blue(), other.blue()),
+            alpha,
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Increases the HSL lightness of the color by `amount`, clamped to `1.0`.
+    pub fn lighten(&self, amount: f32) -> RGBA {
+        let mut hsl = self.to_hsl();
+        hsl.lightness = (hsl.lightness + amount).clamp(0.0, 1.0);
+        hsl.to_rgba(self.alpha())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Decreases the HSL lightness of the color by `amount`, clamped to `0.0`.
+    pub fn darken(&self, amount: f32) -> RGBA {
+        self.lighten(-amount)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Composites this color over `background`, with the Porter-Duff "over"
+    /// operator.
+    pub fn over(&self, background: &RGBA) -> RGBA {
+        let alpha = self.alpha() + background.alpha() * (1.0 - self.alpha());
+        if alpha <= 0.0 {
+            return RGBA::new(0.0, 0.0, 0.0, 0.0);
+        }
+        let over = |fg: f32, bg: f32| {
+            (fg * self.alpha() + bg * background.alpha() * (1.0 - self.alpha())) / alpha
+        };
+        RGBA::new(
+            over(self.red(), background.red()),
+            over(self.green(), background.green()),
+            over(self.blue(), background.blue()),
+            alpha,
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The relative luminance of the color, as defined by WCAG 2, ignoring the
+    /// alpha channel.
+    pub fn relative_luminance(&self) -> f32 {
+        let rgb = self.to_linear_rgb();
+        0.2126 * rgb.red + 0.7152 * rgb.green + 0.0722 * rgb.blue
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The WCAG 2 contrast ratio between both colors, from `1.0` to `21.0`.
+    ///
+    /// Translucent colors should be composited with [`over`](Self::over) on
+    /// their background first.
+    pub fn contrast_ratio(&self, other: &RGBA) -> f32 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Formats the color as `#rrggbb`, or `#rrggbbaa` if it is not opaque.
+    pub fn to_hex(&self) -> String {
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let hex = format!(
+            "#{:02x}{:02x}{:02x}",
+            channel(self.red()),
+            channel(self.green()),
+            channel(self.blue())
+        );
+        if channel(self.alpha()) == 255 {
+            hex
+        } else {
+            format!("{}{:02x}", hex, channel(self.alpha()))
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Parses a color in the `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` forms.
+    pub fn from_hex(s: &str) -> Result<RGBA, glib::error::BoolError> {
+        skip_assert_initialized!();
+        let hex = s
+            .strip_prefix('#')
+            .ok_or_else(|| glib::bool_error!("Invalid hex color {:?}", s))?;
+        let digits = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as f32))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| glib::bool_error!("Invalid hex color {:?}", s))?;
+        let channels = match digits.len() {
+            3 | 4 => digits.iter().map(|d| d * 17.0 / 255.0).collect::<Vec<_>>(),
+            6 | 8 => digits
+                .chunks(2)
+                .map(|pair| (pair[0] * 16.0 + pair[1]) / 255.0)
+                .collect(),
+            _ => return Err(glib::bool_error!("Invalid hex color {:?}", s)),
+        };
+        Ok(RGBA::new(
+            channels[0],
+            channels[1],
+            channels[2],
+            channels.get(3).copied().unwrap_or(1.0),
+        ))
+    }
+
     pub const BLACK: RGBA = RGBA(ffi::GdkRGBA {
         red: 0f32,
         green: 0f32,
@@ -184,3 +301,153 @@ impl FromStr for RGBA {
         RGBA::parse(s)
     }
 }
+
+#[cfg(feature = "serde")]
+const FIELDS: &[&str] = &["red", "green", "blue", "alpha"];
+
+// RGBA is always serialized as a struct of its components. Human readable
+// formats can also give a sequence of components, with an optional alpha, or
+// any string accepted by `RGBA::from_hex` or `RGBA::parse`.
+#[cfg(feature = "serde")]
+struct RGBAVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for RGBAVisitor {
+    type Value = RGBA;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a color as components or as a string")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<RGBA, E> {
+        RGBA::from_hex(s)
+            .or_else(|_| RGBA::parse(s))
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(s), &self))
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<RGBA, A::Error> {
+        let mut component = |i: usize| -> Result<f32, A::Error> {
+            seq.next_element()?
+                .ok_or_else(|| serde::de::Error::invalid_length(i, &self))
+        };
+        let (red, green, blue) = (component(0)?, component(1)?, component(2)?);
+        let alpha = seq.next_element()?.unwrap_or(1.0);
+        Ok(RGBA::new(red, green, blue, alpha))
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<RGBA, A::Error> {
+        let mut components = [None; 4];
+        while let Some(key) = map.next_key::<String>()? {
+            let i = FIELDS
+                .iter()
+                .position(|field| *field == key)
+                .ok_or_else(|| <A::Error as serde::de::Error>::unknown_field(&key, FIELDS))?;
+            if components[i].is_some() {
+                return Err(serde::de::Error::duplicate_field(FIELDS[i]));
+            }
+            components[i] = Some(map.next_value::<f32>()?);
+        }
+        let component = |i: usize| -> Result<f32, A::Error> {
+            components[i].ok_or_else(|| serde::de::Error::missing_field(FIELDS[i]))
+        };
+        Ok(RGBA::new(
+            component(0)?,
+            component(1)?,
+            component(2)?,
+            components[3].unwrap_or(1.0),
+        ))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RGBA {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("RGBA", 4)?;
+        state.serialize_field("red", &self.red())?;
+        state.serialize_field("green", &self.green())?;
+        state.serialize_field("blue", &self.blue())?;
+        state.serialize_field("alpha", &self.alpha())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RGBA {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(RGBAVisitor)
+        } else {
+            deserializer.deserialize_struct("RGBA", FIELDS, RGBAVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(RGBA::from_hex("#f00").unwrap(), RGBA::RED);
+        assert_eq!(
+            RGBA::from_hex("#00ff0080").unwrap(),
+            RGBA::new(0.0, 1.0, 0.0, 128.0 / 255.0)
+        );
+        assert_eq!(
+            RGBA::new(0.0, 1.0, 0.0, 128.0 / 255.0).to_hex(),
+            "#00ff0080"
+        );
+        assert_eq!(RGBA::BLUE.to_hex(), "#0000ff");
+        assert!(RGBA::from_hex("f00").is_err());
+        assert!(RGBA::from_hex("#ff00").is_ok());
+        assert!(RGBA::from_hex("#ff000").is_err());
+        assert!(RGBA::from_hex("#gg0000").is_err());
+    }
+
+    #[test]
+    fn test_compositing() {
+        assert_eq!(
+            RGBA::RED.mix(&RGBA::BLUE, 0.5),
+            RGBA::new(0.5, 0.0, 0.5, 1.0)
+        );
+        let transparent = RGBA::new(0.0, 0.0, 0.0, 0.0);
+        assert_eq!(
+            transparent.mix(&RGBA::RED, 0.5),
+            RGBA::new(1.0, 0.0, 0.0, 0.5)
+        );
+
+        let translucent = RGBA::new(1.0, 0.0, 0.0, 0.5);
+        assert_eq!(translucent.over(&RGBA::BLUE), RGBA::new(0.5, 0.0, 0.5, 1.0));
+        assert_eq!(translucent.over(&transparent), translucent);
+    }
+
+    #[test]
+    fn test_contrast() {
+        assert_eq!(RGBA::WHITE.relative_luminance(), 1.0);
+        assert_eq!(RGBA::BLACK.relative_luminance(), 0.0);
+        assert!((RGBA::BLACK.contrast_ratio(&RGBA::WHITE) - 21.0).abs() < 1e-4);
+        assert!((RGBA::WHITE.contrast_ratio(&RGBA::BLACK) - 21.0).abs() < 1e-4);
+        assert_eq!(RGBA::RED.contrast_ratio(&RGBA::RED), 1.0);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_serde() {
+        let color = RGBA::new(1.0, 0.5, 0.0, 0.25);
+        let json = serde_json::to_string(&color).unwrap();
+        assert_eq!(json, r#"{"red":1.0,"green":0.5,"blue":0.0,"alpha":0.25}"#);
+        assert_eq!(serde_json::from_str::<RGBA>(&json).unwrap(), color);
+
+        let parse = |json: &str| serde_json::from_str::<RGBA>(json);
+        assert_eq!(parse(r#"{"red":1,"green":0,"blue":0}"#).unwrap(), RGBA::RED);
+        assert_eq!(parse("[0, 0, 1]").unwrap(), RGBA::BLUE);
+        assert_eq!(parse(r##""#ff0000""##).unwrap(), RGBA::RED);
+        assert_eq!(parse(r#""blue""#).unwrap(), RGBA::BLUE);
+        assert!(parse(r#"{"red":1,"green":0}"#).is_err());
+        assert!(parse(r#"{"red":1,"green":0,"blue":0,"hue":0}"#).is_err());
+        assert!(parse("[0, 0]").is_err());
+        assert!(parse(r#""not a color""#).is_err());
+    }
+}
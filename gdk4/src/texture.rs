@@ -1,14 +1,29 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
-use crate::Texture;
+use crate::prelude::*;
+use crate::{MemoryFormat, Texture, TextureData};
 use glib::translate::*;
-use glib::IsA;
 
 // rustdoc-stripper-ignore-next
 /// Trait containing manually implemented methods of [`Texture`](crate::Texture).
 pub trait TextureExtManual: 'static {
     #[doc(alias = "gdk_texture_download")]
     fn download(&self, data: &mut [u8], stride: usize);
+
+    // rustdoc-stripper-ignore-next
+    /// Downloads the texture as premultiplied RGBA floats, where `stride` is
+    /// the number of floats per row.
+    #[cfg(any(feature = "v4_6", feature = "dox"))]
+    #[cfg_attr(feature = "dox", doc(cfg(feature = "v4_6")))]
+    #[doc(alias = "gdk_texture_download_float")]
+    fn download_float(&self, data: &mut [f32], stride: usize);
+
+    // rustdoc-stripper-ignore-next
+    /// Downloads the texture into a [`TextureData`](crate::TextureData) in
+    /// `format`.
+    ///
+    /// Fails if `format` is unknown to these bindings.
+    fn download_data(&self, format: MemoryFormat) -> Result<TextureData, glib::BoolError>;
 }
 
 impl<O: IsA<Texture>> TextureExtManual for O {
@@ -17,4 +32,18 @@ impl<O: IsA<Texture>> TextureExtManual for O {
             ffi::gdk_texture_download(self.as_ref().to_glib_none().0, data.as_mut_ptr(), stride);
         }
     }
+
+    #[cfg(any(feature = "v4_6", feature = "dox"))]
+    fn download_float(&self, data: &mut [f32], stride: usize) {
+        let texture = self.as_ref();
+        assert!(stride >= texture.width() as usize * 4);
+        assert!(data.len() >= stride * texture.height() as usize);
+        unsafe {
+            ffi::gdk_texture_download_float(texture.to_glib_none().0, data.as_mut_ptr(), stride);
+        }
+    }
+
+    fn download_data(&self, format: MemoryFormat) -> Result<TextureData, glib::BoolError> {
+        TextureData::download(self, format)
+    }
 }
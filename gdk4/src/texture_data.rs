@@ -0,0 +1,651 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use crate::{MemoryFormat, MemoryTexture, Texture, RGBA};
+
+#[derive(Clone, Copy)]
+enum ChannelType {
+    U8,
+    #[cfg(any(feature = "v4_6", feature = "dox"))]
+    U16,
+    #[cfg(any(feature = "v4_6", feature = "dox"))]
+    F16,
+    #[cfg(any(feature = "v4_6", feature = "dox"))]
+    F32,
+}
+
+impl ChannelType {
+    fn size(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            Self::U16 | Self::F16 => 2,
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            Self::F32 => 4,
+        }
+    }
+
+    fn read(self, bytes: &[u8]) -> f32 {
+        match self {
+            Self::U8 => bytes[0] as f32 / 255.0,
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            Self::U16 => u16::from_ne_bytes([bytes[0], bytes[1]]) as f32 / 65535.0,
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            Self::F16 => f16_to_f32(u16::from_ne_bytes([bytes[0], bytes[1]])),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            Self::F32 => f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+
+    fn write(self, value: f32, bytes: &mut [u8]) {
+        match self {
+            Self::U8 => bytes[0] = (value.clamp(0.0, 1.0) * 255.0).round() as u8,
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            Self::U16 => bytes
+                .copy_from_slice(&((value.clamp(0.0, 1.0) * 65535.0).round() as u16).to_ne_bytes()),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            Self::F16 => bytes.copy_from_slice(&f32_to_f16(value).to_ne_bytes()),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            Self::F32 => bytes.copy_from_slice(&value.to_ne_bytes()),
+        }
+    }
+}
+
+#[cfg(any(feature = "v4_6", feature = "dox"))]
+fn f16_to_f32(half: u16) -> f32 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let mantissa = (half & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+#[cfg(any(feature = "v4_6", feature = "dox"))]
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    if value.is_nan() {
+        return 0x7e00;
+    }
+    let abs = value.abs();
+    if abs >= 65520.0 {
+        sign | 0x7c00
+    } else if abs < 6.103_515_6e-5 {
+        // Subnormal halves are multiples of 2^-24.
+        sign | (abs * 16_777_216.0).round() as u16
+    } else {
+        let exponent = abs.log2().floor() as i32;
+        let mantissa = ((abs / 2f32.powi(exponent) - 1.0) * 1024.0).round() as u32;
+        // Rounding the mantissa up may carry into the exponent.
+        let half = (((exponent + 15) as u32) << 10) + mantissa;
+        sign | half as u16
+    }
+}
+
+// Where each channel of a memory format is, in the order red, green, blue and
+// alpha.
+struct Layout {
+    channel_type: ChannelType,
+    n_channels: usize,
+    offsets: [usize; 3],
+    alpha: Option<usize>,
+    premultiplied: bool,
+}
+
+impl Layout {
+    fn new(format: MemoryFormat) -> Option<Self> {
+        use ChannelType::*;
+        let (channel_type, offsets, alpha, premultiplied) = match format {
+            MemoryFormat::B8g8r8a8Premultiplied => (U8, [2, 1, 0], Some(3), true),
+            MemoryFormat::A8r8g8b8Premultiplied => (U8, [1, 2, 3], Some(0), true),
+            MemoryFormat::R8g8b8a8Premultiplied => (U8, [0, 1, 2], Some(3), true),
+            MemoryFormat::B8g8r8a8 => (U8, [2, 1, 0], Some(3), false),
+            MemoryFormat::A8r8g8b8 => (U8, [1, 2, 3], Some(0), false),
+            MemoryFormat::R8g8b8a8 => (U8, [0, 1, 2], Some(3), false),
+            MemoryFormat::A8b8g8r8 => (U8, [3, 2, 1], Some(0), false),
+            MemoryFormat::R8g8b8 => (U8, [0, 1, 2], None, false),
+            MemoryFormat::B8g8r8 => (U8, [2, 1, 0], None, false),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            MemoryFormat::R16g16b16 => (U16, [0, 1, 2], None, false),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            MemoryFormat::R16g16b16a16Premultiplied => (U16, [0, 1, 2], Some(3), true),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            MemoryFormat::R16g16b16a16 => (U16, [0, 1, 2], Some(3), false),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            MemoryFormat::R16g16b16Float => (F16, [0, 1, 2], None, false),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            MemoryFormat::R16g16b16a16FloatPremultiplied => (F16, [0, 1, 2], Some(3), true),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            MemoryFormat::R16g16b16a16Float => (F16, [0, 1, 2], Some(3), false),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            MemoryFormat::R32g32b32Float => (F32, [0, 1, 2], None, false),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            MemoryFormat::R32g32b32a32FloatPremultiplied => (F32, [0, 1, 2], Some(3), true),
+            #[cfg(any(feature = "v4_6", feature = "dox"))]
+            MemoryFormat::R32g32b32a32Float => (F32, [0, 1, 2], Some(3), false),
+            _ => return None,
+        };
+        Some(Self {
+            channel_type,
+            n_channels: if alpha.is_some() { 4 } else { 3 },
+            offsets,
+            alpha,
+            premultiplied,
+        })
+    }
+
+    fn bytes_per_pixel(&self) -> usize {
+        self.n_channels * self.channel_type.size()
+    }
+
+    fn read(&self, pixel: &[u8]) -> RGBA {
+        let size = self.channel_type.size();
+        let channel = |idx: usize| self.channel_type.read(&pixel[idx * size..]);
+        let alpha = self.alpha.map_or(1.0, channel);
+        let unpremultiply = |value: f32| {
+            if self.premultiplied && alpha > 0.0 {
+                value / alpha
+            } else {
+                value
+            }
+        };
+        RGBA::new(
+            unpremultiply(channel(self.offsets[0])),
+            unpremultiply(channel(self.offsets[1])),
+            unpremultiply(channel(self.offsets[2])),
+            alpha,
+        )
+    }
+
+    fn write(&self, color: &RGBA, pixel: &mut [u8]) {
+        let size = self.channel_type.size();
+        let alpha = if self.alpha.is_some() {
+            color.alpha()
+        } else {
+            1.0
+        };
+        let premultiply = |value: f32| {
+            if self.premultiplied {
+                value * alpha
+            } else {
+                value
+            }
+        };
+        let values = [
+            premultiply(color.red()),
+            premultiply(color.green()),
+            premultiply(color.blue()),
+        ];
+        for (offset, value) in self.offsets.iter().zip(values) {
+            self.channel_type
+                .write(value, &mut pixel[offset * size..(offset + 1) * size]);
+        }
+        if let Some(offset) = self.alpha {
+            self.channel_type
+                .write(alpha, &mut pixel[offset * size..(offset + 1) * size]);
+        }
+    }
+}
+
+// The format `gdk_texture_download` writes, which is cairo's native ARGB32.
+fn download_format() -> MemoryFormat {
+    if cfg!(target_endian = "little") {
+        MemoryFormat::B8g8r8a8Premultiplied
+    } else {
+        MemoryFormat::A8r8g8b8Premultiplied
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// The pixels of a texture in memory, in a known [`MemoryFormat`](crate::MemoryFormat).
+///
+/// Multi-byte channels are stored in native endianness, like GDK expects them.
+///
+/// ```no_run
+/// use gdk4 as gdk;
+/// use gdk::prelude::*;
+///
+/// fn count_opaque(texture: &gdk::Texture) -> usize {
+///     let data = texture.download_data(gdk::MemoryFormat::R8g8b8a8).unwrap();
+///     data.pixels().filter(|pixel| pixel.alpha() >= 1.0).count()
+/// }
+///
+/// let pixels = vec![[255, 0, 0, 255]; 16 * 16];
+/// let red = gdk::TextureData::from_rgba8(16, 16, &pixels).unwrap().to_texture();
+/// assert_eq!(count_opaque(red.upcast_ref()), 16 * 16);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextureData {
+    width: i32,
+    height: i32,
+    format: MemoryFormat,
+    stride: usize,
+    data: Vec<u8>,
+}
+
+impl TextureData {
+    // rustdoc-stripper-ignore-next
+    /// Wraps pixels in `format`, where rows start every `stride` bytes.
+    ///
+    /// Fails if the format is unknown to these bindings, or if `stride` or the
+    /// length of `data` are too small for the dimensions.
+    pub fn new(
+        width: i32,
+        height: i32,
+        format: MemoryFormat,
+        stride: usize,
+        data: Vec<u8>,
+    ) -> Result<Self, glib::BoolError> {
+        skip_assert_initialized!();
+        let layout = Layout::new(format)
+            .ok_or_else(|| glib::bool_error!("Unsupported format {}", format))?;
+        if width <= 0 || height <= 0 {
+            return Err(glib::bool_error!("Invalid size {}x{}", width, height));
+        }
+        let row_size = width as usize * layout.bytes_per_pixel();
+        if stride < row_size {
+            return Err(glib::bool_error!(
+                "Stride {} is smaller than a row of {} bytes",
+                stride,
+                row_size
+            ));
+        }
+        let needed = stride * (height as usize - 1) + row_size;
+        if data.len() < needed {
+            return Err(glib::bool_error!(
+                "{} bytes of data are too few for {}x{} pixels with stride {}, expected {}",
+                data.len(),
+                width,
+                height,
+                stride,
+                needed
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            format,
+            stride,
+            data,
+        })
+    }
+
+    fn from_channels<T: Copy, const N: usize>(
+        width: i32,
+        height: i32,
+        format: MemoryFormat,
+        pixels: &[[T; N]],
+        to_bytes: impl Fn(T) -> Vec<u8>,
+    ) -> Result<Self, glib::BoolError> {
+        if width <= 0 || height <= 0 || pixels.len() != width as usize * height as usize {
+            return Err(glib::bool_error!(
+                "{} pixels don't make an image of {}x{} pixels",
+                pixels.len(),
+                width,
+                height
+            ));
+        }
+        let data: Vec<u8> = pixels
+            .iter()
+            .flat_map(|pixel| pixel.iter().flat_map(|&value| to_bytes(value)))
+            .collect();
+        let stride = data.len() / height as usize;
+        Self::new(width, height, format, stride, data)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Builds an image from tightly packed, straight alpha RGBA pixels.
+    pub fn from_rgba8(
+        width: i32,
+        height: i32,
+        pixels: &[[u8; 4]],
+    ) -> Result<Self, glib::BoolError> {
+        skip_assert_initialized!();
+        Self::from_channels(width, height, MemoryFormat::R8g8b8a8, pixels, |v| vec![v])
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Builds an image from tightly packed RGB pixels.
+    pub fn from_rgb8(width: i32, height: i32, pixels: &[[u8; 3]]) -> Result<Self, glib::BoolError> {
+        skip_assert_initialized!();
+        Self::from_channels(width, height, MemoryFormat::R8g8b8, pixels, |v| vec![v])
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Builds an image from tightly packed, straight alpha RGBA pixels.
+    #[cfg(any(feature = "v4_6", feature = "dox"))]
+    #[cfg_attr(feature = "dox", doc(cfg(feature = "v4_6")))]
+    pub fn from_rgba_f32(
+        width: i32,
+        height: i32,
+        pixels: &[[f32; 4]],
+    ) -> Result<Self, glib::BoolError> {
+        skip_assert_initialized!();
+        Self::from_channels(
+            width,
+            height,
+            MemoryFormat::R32g32b32a32Float,
+            pixels,
+            |v| v.to_ne_bytes().to_vec(),
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Builds an image in `format` from colors, given row by row.
+    pub fn from_colors(
+        width: i32,
+        height: i32,
+        format: MemoryFormat,
+        colors: &[RGBA],
+    ) -> Result<Self, glib::BoolError> {
+        skip_assert_initialized!();
+        let layout = Layout::new(format)
+            .ok_or_else(|| glib::bool_error!("Unsupported format {}", format))?;
+        if width <= 0 || height <= 0 || colors.len() != width as usize * height as usize {
+            return Err(glib::bool_error!(
+                "{} colors don't make an image of {}x{} pixels",
+                colors.len(),
+                width,
+                height
+            ));
+        }
+        let bpp = layout.bytes_per_pixel();
+        let mut data = vec![0; colors.len() * bpp];
+        for (color, pixel) in colors.iter().zip(data.chunks_exact_mut(bpp)) {
+            layout.write(color, pixel);
+        }
+        Self::new(width, height, format, width as usize * bpp, data)
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn format(&self) -> MemoryFormat {
+        self.format
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    fn layout(&self) -> Layout {
+        Layout::new(self.format).unwrap()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The bytes of row `y`, without the padding up to the stride.
+    pub fn row(&self, y: i32) -> Option<&[u8]> {
+        if y < 0 || y >= self.height {
+            return None;
+        }
+        let start = y as usize * self.stride;
+        Some(&self.data[start..start + self.width as usize * self.layout().bytes_per_pixel()])
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The color of the pixel at `x`, `y`, with straight alpha.
+    pub fn pixel(&self, x: i32, y: i32) -> Option<RGBA> {
+        if x < 0 || x >= self.width {
+            return None;
+        }
+        let layout = self.layout();
+        let bpp = layout.bytes_per_pixel();
+        let row = self.row(y)?;
+        Some(layout.read(&row[x as usize * bpp..(x as usize + 1) * bpp]))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Iterates over the colors of all pixels, row by row, with straight alpha.
+    pub fn pixels(&self) -> impl Iterator<Item = RGBA> + '_ {
+        let layout = self.layout();
+        let bpp = layout.bytes_per_pixel();
+        (0..self.height)
+            .filter_map(move |y| self.row(y))
+            .flat_map(move |row| row.chunks_exact(bpp))
+            .map(move |pixel| layout.read(pixel))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Converts the pixels to `format`, with tightly packed rows.
+    pub fn convert(&self, format: MemoryFormat) -> Result<Self, glib::BoolError> {
+        if format == self.format {
+            let stride = self.width as usize * self.layout().bytes_per_pixel();
+            let data = (0..self.height)
+                .filter_map(|y| self.row(y))
+                .flatten()
+                .copied()
+                .collect();
+            return Self::new(self.width, self.height, format, stride, data);
+        }
+        let colors: Vec<RGBA> = self.pixels().collect();
+        Self::from_colors(self.width, self.height, format, &colors)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Creates a texture from the pixels.
+    pub fn to_texture(&self) -> MemoryTexture {
+        MemoryTexture::new(
+            self.width,
+            self.height,
+            self.format,
+            &glib::Bytes::from(&self.data[..]),
+            self.stride,
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Downloads the pixels of `texture` and converts them to `format`.
+    pub fn download(
+        texture: &impl IsA<Texture>,
+        format: MemoryFormat,
+    ) -> Result<Self, glib::BoolError> {
+        skip_assert_initialized!();
+        let texture = texture.as_ref();
+        let (width, height) = (texture.width(), texture.height());
+        let stride = width as usize * 4;
+        #[cfg(any(feature = "v4_6", feature = "dox"))]
+        if Layout::new(format).map_or(false, |layout| {
+            !matches!(layout.channel_type, ChannelType::U8)
+        }) {
+            // Don't lose precision by going through 8-bit channels.
+            let mut floats = vec![0.0; stride * height as usize];
+            texture.download_float(&mut floats, stride);
+            let data = floats.iter().flat_map(|v| v.to_ne_bytes()).collect();
+            return Self::new(
+                width,
+                height,
+                MemoryFormat::R32g32b32a32FloatPremultiplied,
+                stride * 4,
+                data,
+            )?
+            .convert(format);
+        }
+        let mut data = vec![0; stride * height as usize];
+        texture.download(&mut data, stride);
+        Self::new(width, height, download_format(), stride, data)?.convert(format)
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<&TextureData> for image::RgbaImage {
+    fn from(data: &TextureData) -> Self {
+        let rgba = data.convert(MemoryFormat::R8g8b8a8).unwrap();
+        image::RgbaImage::from_raw(rgba.width as u32, rgba.height as u32, rgba.data).unwrap()
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<&TextureData> for image::Rgba32FImage {
+    fn from(data: &TextureData) -> Self {
+        let pixels = data
+            .pixels()
+            .flat_map(|color| [color.red(), color.green(), color.blue(), color.alpha()])
+            .collect();
+        image::Rgba32FImage::from_raw(data.width as u32, data.height as u32, pixels).unwrap()
+    }
+}
+
+#[cfg(feature = "image")]
+impl TryFrom<&image::RgbaImage> for TextureData {
+    type Error = glib::BoolError;
+
+    fn try_from(image: &image::RgbaImage) -> Result<Self, Self::Error> {
+        skip_assert_initialized!();
+        let (width, height) = image.dimensions();
+        Self::new(
+            width as i32,
+            height as i32,
+            MemoryFormat::R8g8b8a8,
+            width as usize * 4,
+            image.as_raw().clone(),
+        )
+    }
+}
+
+#[cfg(feature = "image")]
+impl TryFrom<&image::RgbImage> for TextureData {
+    type Error = glib::BoolError;
+
+    fn try_from(image: &image::RgbImage) -> Result<Self, Self::Error> {
+        skip_assert_initialized!();
+        let (width, height) = image.dimensions();
+        Self::new(
+            width as i32,
+            height as i32,
+            MemoryFormat::R8g8b8,
+            width as usize * 3,
+            image.as_raw().clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_packs_rows() {
+        let mut data = vec![0; 12 + 8];
+        data[..8].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        data[12..].copy_from_slice(&[9, 10, 11, 12, 13, 14, 15, 16]);
+        let padded = TextureData::new(2, 2, MemoryFormat::R8g8b8a8, 12, data).unwrap();
+
+        let packed = padded.convert(MemoryFormat::R8g8b8a8).unwrap();
+        assert_eq!(packed.stride(), 8);
+        assert_eq!(packed.data(), (1..=16).collect::<Vec<u8>>());
+        assert_eq!(
+            packed.pixels().collect::<Vec<_>>(),
+            padded.pixels().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_convert_premultiplied() {
+        let color = RGBA::new(1.0, 0.5, 0.0, 0.5);
+        let straight = TextureData::from_colors(1, 1, MemoryFormat::R8g8b8a8, &[color]).unwrap();
+        assert_eq!(straight.data(), [255, 128, 0, 128]);
+
+        let premultiplied = straight
+            .convert(MemoryFormat::B8g8r8a8Premultiplied)
+            .unwrap();
+        assert_eq!(premultiplied.data(), [0, 64, 128, 128]);
+        let back = premultiplied.convert(MemoryFormat::R8g8b8a8).unwrap();
+        assert_eq!(back.data(), straight.data());
+
+        // Formats without alpha drop it without premultiplying.
+        let opaque = straight.convert(MemoryFormat::R8g8b8).unwrap();
+        assert_eq!(opaque.data(), [255, 128, 0]);
+        assert_eq!(opaque.pixel(0, 0).unwrap().alpha(), 1.0);
+
+        // Fully transparent pixels lose their color when premultiplied.
+        let clear = TextureData::from_rgba8(1, 1, &[[255, 255, 255, 0]]).unwrap();
+        let converted = clear
+            .convert(MemoryFormat::R8g8b8a8Premultiplied)
+            .unwrap()
+            .convert(MemoryFormat::R8g8b8a8)
+            .unwrap();
+        assert_eq!(converted.data(), [0, 0, 0, 0]);
+    }
+
+    #[cfg(any(feature = "v4_6", feature = "dox"))]
+    #[test]
+    fn test_convert_float() {
+        let pixels = [[255, 128, 0, 128], [10, 20, 30, 255]];
+        let bytes = TextureData::from_rgba8(2, 1, &pixels).unwrap();
+        for format in [
+            MemoryFormat::R16g16b16a16,
+            MemoryFormat::R16g16b16a16Premultiplied,
+            MemoryFormat::R16g16b16a16Float,
+            MemoryFormat::R16g16b16a16FloatPremultiplied,
+            MemoryFormat::R32g32b32a32Float,
+            MemoryFormat::R32g32b32a32FloatPremultiplied,
+        ] {
+            let converted = bytes.convert(format).unwrap();
+            assert_eq!(converted.format(), format);
+            let back = converted.convert(MemoryFormat::R8g8b8a8).unwrap();
+            assert_eq!(back.data(), bytes.data(), "{}", format);
+        }
+
+        let colors = [RGBA::new(0.1, 0.25, 1.0, 0.75)];
+        let floats =
+            TextureData::from_colors(1, 1, MemoryFormat::R32g32b32a32Float, &colors).unwrap();
+        let halves = floats
+            .convert(MemoryFormat::R16g16b16a16FloatPremultiplied)
+            .unwrap();
+        let color = halves.pixel(0, 0).unwrap();
+        for (value, expected) in [
+            (color.red(), 0.1),
+            (color.green(), 0.25),
+            (color.blue(), 1.0),
+            (color.alpha(), 0.75),
+        ] {
+            assert!((value - expected).abs() < 1e-3, "{} != {}", value, expected);
+        }
+    }
+
+    #[cfg(any(feature = "v4_6", feature = "dox"))]
+    #[test]
+    fn test_f16() {
+        assert_eq!(f32_to_f16(0.0), 0x0000);
+        assert_eq!(f32_to_f16(-0.0), 0x8000);
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f32_to_f16(-2.0), 0xc000);
+        assert_eq!(f32_to_f16(0.1), 0x2e66);
+        assert_eq!(f32_to_f16(65504.0), 0x7bff);
+        assert_eq!(f32_to_f16(65520.0), 0x7c00);
+        assert_eq!(f32_to_f16(f32::NEG_INFINITY), 0xfc00);
+        assert_eq!(f32_to_f16(f32::NAN), 0x7e00);
+        // The smallest subnormal, and the carry of a mantissa rounded up.
+        assert_eq!(f32_to_f16(2f32.powi(-24)), 0x0001);
+        assert_eq!(f32_to_f16(1.9999), 0x4000);
+
+        assert_eq!(f16_to_f32(0x3c00), 1.0);
+        assert_eq!(f16_to_f32(0x0400), 2f32.powi(-14));
+        assert_eq!(f16_to_f32(0xfc00), f32::NEG_INFINITY);
+        assert!(f16_to_f32(0x7e00).is_nan());
+
+        for half in 0..=u16::MAX {
+            let value = f16_to_f32(half);
+            if !value.is_nan() {
+                assert_eq!(f32_to_f16(value), half, "{:#06x}", half);
+            }
+        }
+    }
+}
@@ -27,6 +27,7 @@ macro_rules! skip_assert_initialized {
 #[allow(unused_imports)]
 mod auto;
 
+pub mod offscreen;
 pub mod prelude;
 // rustdoc-stripper-ignore-next
 /// Builder pattern types.
@@ -38,9 +39,20 @@ pub use auto::*;
 
 #[macro_use]
 mod render_node;
+mod node_builder;
+mod node_path;
+mod render_node_diff;
+mod render_node_lint;
+mod render_node_parse;
+mod render_node_pick;
+mod render_node_svg;
+mod render_node_visitor;
 mod renderer;
 mod rounded_rect;
+mod shader_args;
+mod shader_watcher;
 mod shadow;
+mod transform;
 
 // Render node types
 mod blend_node;
@@ -94,18 +106,27 @@ pub use linear_gradient_node::LinearGradientNode;
 #[cfg_attr(feature = "dox", doc(cfg(feature = "v4_2")))]
 #[cfg_attr(feature = "v4_4", deprecated = "Since 4.4")]
 pub use ngl_renderer::NglRenderer;
+pub use node_builder::NodeBuilder;
+pub use node_path::NodePath;
 pub use opacity_node::OpacityNode;
 pub use outset_shadow_node::OutsetShadowNode;
 pub use parse_location::ParseLocation;
 pub use radial_gradient_node::RadialGradientNode;
 pub use render_node::RenderNode;
+pub use render_node_diff::NodeChange;
+pub use render_node_lint::{NodeLint, NodeLintKind};
+pub use render_node_parse::{NodeParseError, SourcePosition};
+pub use render_node_visitor::{Descendants, RenderNodeVisitor};
 pub use repeat_node::RepeatNode;
 pub use repeating_linear_gradient_node::RepeatingLinearGradientNode;
 pub use repeating_radial_gradient_node::RepeatingRadialGradientNode;
 pub use rounded_clip_node::RoundedClipNode;
 pub use rounded_rect::RoundedRect;
+pub use shader_args::{ShaderArg, ShaderArgs, TypedGLShader};
+pub use shader_watcher::{ShaderChangedCallbackId, ShaderError, ShaderWatcher};
 pub use shadow::Shadow;
 pub use shadow_node::ShadowNode;
 pub use text_node::TextNode;
 pub use texture_node::TextureNode;
+pub use transform::Decomposed;
 pub use transform_node::TransformNode;
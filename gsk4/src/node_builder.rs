@@ -0,0 +1,261 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use crate::{
+    BlurNode, BorderNode, ClipNode, ColorMatrixNode, ColorNode, ColorStop, ContainerNode,
+    DebugNode, LinearGradientNode, OpacityNode, RadialGradientNode, RenderNode, RepeatNode,
+    RoundedClipNode, RoundedRect, Shadow, ShadowNode, TextNode, TextureNode, Transform,
+    TransformNode,
+};
+use graphene::{Matrix, Point, Rect, Vec4};
+
+// What a pushed state wraps its children in when it is popped.
+#[derive(Debug)]
+enum Wrapper {
+    Root,
+    Blur(f32),
+    Clip(Rect),
+    ColorMatrix(Matrix, Vec4),
+    Debug(String),
+    Opacity(f32),
+    Repeat(Rect, Option<Rect>),
+    RoundedClip(RoundedRect),
+    Shadow(Vec<Shadow>),
+    Transform(Transform),
+}
+
+impl Wrapper {
+    fn wrap(self, child: RenderNode) -> RenderNode {
+        match self {
+            Self::Root => child,
+            Self::Blur(radius) => BlurNode::new(&child, radius).upcast(),
+            Self::Clip(clip) => ClipNode::new(&child, &clip).upcast(),
+            Self::ColorMatrix(matrix, offset) => {
+                ColorMatrixNode::new(&child, &matrix, &offset).upcast()
+            }
+            Self::Debug(message) => DebugNode::new(&child, &message).upcast(),
+            Self::Opacity(opacity) => OpacityNode::new(&child, opacity).upcast(),
+            Self::Repeat(bounds, child_bounds) => {
+                RepeatNode::new(&bounds, &child, child_bounds.as_ref()).upcast()
+            }
+            Self::RoundedClip(clip) => RoundedClipNode::new(&child, &clip).upcast(),
+            Self::Shadow(shadows) => ShadowNode::new(&child, &shadows).upcast(),
+            Self::Transform(transform) => TransformNode::new(&child, &transform).upcast(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    wrapper: Wrapper,
+    nodes: Vec<RenderNode>,
+}
+
+// rustdoc-stripper-ignore-next
+/// Builds a tree of [`RenderNode`](crate::RenderNode)s with a stack of states,
+/// like `gtk::Snapshot` but without needing any widget.
+///
+/// Each `push_*` call starts a new state, and the nodes appended until the
+/// matching [`pop`](Self::pop) are wrapped in the node of that state.
+///
+/// ```
+/// use gsk4 as gsk;
+/// use gsk::{gdk, graphene};
+///
+/// let rect = graphene::Rect::new(0.0, 0.0, 100.0, 100.0);
+/// let mut builder = gsk::NodeBuilder::new();
+/// builder.push_clip(&graphene::Rect::new(0.0, 0.0, 50.0, 50.0));
+/// builder.color(&gdk::RGBA::RED, &rect);
+/// builder.pop();
+/// let node = builder.build().unwrap();
+/// assert_eq!(node.node_type(), gsk::RenderNodeType::ClipNode);
+/// ```
+#[derive(Debug)]
+pub struct NodeBuilder {
+    states: Vec<State>,
+}
+
+impl Default for NodeBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NodeBuilder {
+    pub fn new() -> Self {
+        assert_initialized_main_thread!();
+        Self {
+            states: vec![State {
+                wrapper: Wrapper::Root,
+                nodes: Vec::new(),
+            }],
+        }
+    }
+
+    fn push(&mut self, wrapper: Wrapper) -> &mut Self {
+        self.states.push(State {
+            wrapper,
+            nodes: Vec::new(),
+        });
+        self
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Appends an already built node to the current state.
+    pub fn append_node<P: AsRef<RenderNode>>(&mut self, node: &P) -> &mut Self {
+        self.states
+            .last_mut()
+            .unwrap()
+            .nodes
+            .push(node.as_ref().clone());
+        self
+    }
+
+    pub fn push_blur(&mut self, radius: f32) -> &mut Self {
+        self.push(Wrapper::Blur(radius))
+    }
+
+    pub fn push_clip(&mut self, clip: &Rect) -> &mut Self {
+        self.push(Wrapper::Clip(*clip))
+    }
+
+    pub fn push_color_matrix(&mut self, color_matrix: &Matrix, color_offset: &Vec4) -> &mut Self {
+        self.push(Wrapper::ColorMatrix(*color_matrix, *color_offset))
+    }
+
+    pub fn push_debug(&mut self, message: &str) -> &mut Self {
+        self.push(Wrapper::Debug(message.to_owned()))
+    }
+
+    pub fn push_opacity(&mut self, opacity: f32) -> &mut Self {
+        self.push(Wrapper::Opacity(opacity))
+    }
+
+    pub fn push_repeat(&mut self, bounds: &Rect, child_bounds: Option<&Rect>) -> &mut Self {
+        self.push(Wrapper::Repeat(*bounds, child_bounds.copied()))
+    }
+
+    pub fn push_rounded_clip(&mut self, clip: &RoundedRect) -> &mut Self {
+        self.push(Wrapper::RoundedClip(clip.clone()))
+    }
+
+    pub fn push_shadow(&mut self, shadows: &[Shadow]) -> &mut Self {
+        self.push(Wrapper::Shadow(shadows.to_vec()))
+    }
+
+    pub fn push_transform(&mut self, transform: &Transform) -> &mut Self {
+        self.push(Wrapper::Transform(transform.clone()))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Shortcut for [`push_transform`](Self::push_transform) with a translation.
+    pub fn push_translate(&mut self, point: &Point) -> &mut Self {
+        let transform = Transform::new().translate(point).unwrap_or_default();
+        self.push_transform(&transform)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Closes the state opened by the last `push_*` call and appends its node to
+    /// the parent state.
+    ///
+    /// States without any nodes are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no matching `push_*` call.
+    pub fn pop(&mut self) -> &mut Self {
+        assert!(
+            self.states.len() > 1,
+            "NodeBuilder::pop() called without a matching push"
+        );
+        let state = self.states.pop().unwrap();
+        if let Some(node) = Self::finish(state) {
+            self.append_node(&node);
+        }
+        self
+    }
+
+    fn finish(state: State) -> Option<RenderNode> {
+        let State { wrapper, mut nodes } = state;
+        let child = match nodes.len() {
+            0 => return None,
+            1 => nodes.pop().unwrap(),
+            _ => ContainerNode::new(&nodes).upcast(),
+        };
+        Some(wrapper.wrap(child))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the built tree, or `None` if no node was appended.
+    ///
+    /// States that have not been popped are closed first.
+    pub fn build(mut self) -> Option<RenderNode> {
+        while self.states.len() > 1 {
+            self.pop();
+        }
+        Self::finish(self.states.pop().unwrap())
+    }
+
+    pub fn border(
+        &mut self,
+        outline: &RoundedRect,
+        border_width: &[f32; 4],
+        border_color: &[gdk::RGBA; 4],
+    ) -> &mut Self {
+        self.append_node(&BorderNode::new(outline, border_width, border_color))
+    }
+
+    pub fn color(&mut self, color: &gdk::RGBA, bounds: &Rect) -> &mut Self {
+        self.append_node(&ColorNode::new(color, bounds))
+    }
+
+    pub fn linear_gradient(
+        &mut self,
+        bounds: &Rect,
+        start: &Point,
+        end: &Point,
+        color_stops: &[ColorStop],
+    ) -> &mut Self {
+        self.append_node(&LinearGradientNode::new(bounds, start, end, color_stops))
+    }
+
+    pub fn radial_gradient(
+        &mut self,
+        bounds: &Rect,
+        center: &Point,
+        hradius: f32,
+        vradius: f32,
+        start: f32,
+        end: f32,
+        color_stops: &[ColorStop],
+    ) -> &mut Self {
+        self.append_node(&RadialGradientNode::new(
+            bounds,
+            center,
+            hradius,
+            vradius,
+            start,
+            end,
+            color_stops,
+        ))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Appends a [`TextNode`](crate::TextNode), unless `glyphs` has nothing to draw.
+    pub fn text<P: IsA<pango::Font>>(
+        &mut self,
+        font: &P,
+        glyphs: &mut pango::GlyphString,
+        color: &gdk::RGBA,
+        offset: &Point,
+    ) -> &mut Self {
+        if let Some(node) = TextNode::new(font, glyphs, color, offset) {
+            self.append_node(&node);
+        }
+        self
+    }
+
+    pub fn texture<P: IsA<gdk::Texture>>(&mut self, texture: &P, bounds: &Rect) -> &mut Self {
+        self.append_node(&TextureNode::new(texture, bounds))
+    }
+}
@@ -0,0 +1,72 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::RenderNode;
+use std::fmt;
+
+// rustdoc-stripper-ignore-next
+/// The position of a node in a tree of [`RenderNode`](crate::RenderNode)s.
+///
+/// A path is the list of child indices, as returned by
+/// [`RenderNode::children`](crate::RenderNode::children), to follow from the root
+/// of the tree. It is displayed as `/` for the root and like `/0/2` otherwise.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodePath(Vec<usize>);
+
+impl NodePath {
+    // rustdoc-stripper-ignore-next
+    /// The path of the root node.
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    pub fn indices(&self) -> &[usize] {
+        &self.0
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The path of the child at `idx` of the node at this path.
+    pub fn child(&self, idx: usize) -> Self {
+        let mut indices = self.0.clone();
+        indices.push(idx);
+        Self(indices)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The path of the parent node, or `None` for the root.
+    pub fn parent(&self) -> Option<Self> {
+        let (_, parent) = self.0.split_last()?;
+        Some(Self(parent.to_vec()))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Finds the node at this path in the tree starting at `root`.
+    pub fn resolve(&self, root: &RenderNode) -> Option<RenderNode> {
+        let mut node = root.clone();
+        for &idx in &self.0 {
+            node = node.children().into_iter().nth(idx)?;
+        }
+        Some(node)
+    }
+}
+
+impl From<Vec<usize>> for NodePath {
+    fn from(indices: Vec<usize>) -> Self {
+        Self(indices)
+    }
+}
+
+impl fmt::Display for NodePath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_empty() {
+            return f.write_str("/");
+        }
+        for idx in &self.0 {
+            write!(f, "/{}", idx)?;
+        }
+        Ok(())
+    }
+}
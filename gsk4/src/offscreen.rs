@@ -0,0 +1,263 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+// rustdoc-stripper-ignore-next
+//! Rendering of [`RenderNode`](crate::RenderNode)s to memory, without any
+//! display or surface.
+//!
+//! [`render`] uses a [`GLRenderer`](crate::GLRenderer) when the `v4_6` feature
+//! is enabled and the default display can create a GL context without a
+//! surface, and falls back to drawing with cairo, the same way
+//! [`CairoRenderer`](crate::CairoRenderer) does, otherwise. The other functions
+//! always use cairo, so they don't need a GL context nor the main thread.
+//!
+//! Render nodes can't be sent to other threads, but their serialized form can:
+//! [`render_serialized`] deserializes and draws a node on the calling thread,
+//! and returns an [`Image`] that can be sent back to the main thread to create
+//! a texture.
+//!
+//! ```no_run
+//! use gsk4 as gsk;
+//!
+//! let bounds = gsk::graphene::Rect::new(0.0, 0.0, 64.0, 64.0);
+//! let node = gsk::ColorNode::new(&gsk::gdk::RGBA::BLUE, &bounds);
+//! let serialized = node.serialize().to_vec();
+//! let worker = std::thread::spawn(move || gsk::offscreen::render_serialized(&serialized, None, 0.5));
+//! let texture = worker.join().unwrap().unwrap().into_texture();
+//! ```
+
+use crate::RenderNode;
+#[cfg(any(feature = "v4_6", feature = "dox"))]
+use crate::{prelude::*, GLRenderer, Transform, TransformNode};
+use graphene::Rect;
+
+// The largest surface cairo can create.
+const MAX_SIZE: f64 = 32767.0;
+
+// rustdoc-stripper-ignore-next
+/// Premultiplied pixels in the native byte order of cairo, as produced by the
+/// functions of this module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: i32,
+    height: i32,
+    stride: usize,
+    data: Vec<u8>,
+}
+
+impl Image {
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The layout of [`data`](Self::data), which depends on the endianness of
+    /// the target.
+    pub fn format(&self) -> gdk::MemoryFormat {
+        if cfg!(target_endian = "little") {
+            gdk::MemoryFormat::B8g8r8a8Premultiplied
+        } else {
+            gdk::MemoryFormat::A8r8g8b8Premultiplied
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Creates a texture from the pixels. This has to be called on the main thread.
+    pub fn into_texture(self) -> gdk::MemoryTexture {
+        let format = self.format();
+        gdk::MemoryTexture::new(
+            self.width,
+            self.height,
+            format,
+            &glib::Bytes::from_owned(self.data),
+            self.stride,
+        )
+    }
+}
+
+// Checks the arguments of the render functions, and returns the viewport to
+// render and the size of the image in pixels.
+fn image_size(
+    node: &RenderNode,
+    viewport: Option<&Rect>,
+    scale: f64,
+) -> Result<(Rect, i32, i32), glib::BoolError> {
+    let viewport = viewport.copied().unwrap_or_else(|| node.bounds());
+    if !(scale.is_finite() && scale > 0.0) {
+        return Err(glib::bool_error!("Invalid scale {}", scale));
+    }
+    let width = (viewport.width() as f64 * scale).ceil();
+    let height = (viewport.height() as f64 * scale).ceil();
+    if !(width >= 1.0 && height >= 1.0 && width <= MAX_SIZE && height <= MAX_SIZE) {
+        return Err(glib::bool_error!(
+            "Can't render an image of {}x{} pixels",
+            width,
+            height
+        ));
+    }
+    Ok((viewport, width as i32, height as i32))
+}
+
+// rustdoc-stripper-ignore-next
+/// Renders the area of `node` given by `viewport`, or the bounds of the node if
+/// it is `None`, to a texture with `scale` pixels per unit.
+///
+/// This has to be called on the main thread.
+pub fn render(
+    node: &RenderNode,
+    viewport: Option<&Rect>,
+    scale: f64,
+) -> Result<gdk::MemoryTexture, glib::BoolError> {
+    assert_initialized_main_thread!();
+    #[cfg(any(feature = "v4_6", feature = "dox"))]
+    {
+        let (viewport, width, height) = image_size(node, viewport, scale)?;
+        if let Some(image) = render_gl(node, &viewport, scale, width, height) {
+            return Ok(image.into_texture());
+        }
+    }
+    render_image(node, viewport, scale).map(Image::into_texture)
+}
+
+// Renders with a GL renderer realized without a surface, which GTK supports
+// since 4.6. Returns `None` when no GL context is available.
+#[cfg(any(feature = "v4_6", feature = "dox"))]
+fn render_gl(
+    node: &RenderNode,
+    viewport: &Rect,
+    scale: f64,
+    width: i32,
+    height: i32,
+) -> Option<Image> {
+    let renderer = GLRenderer::new();
+    renderer.realize(None).ok()?;
+    // The renderer draws one pixel per unit, so scale the node and the
+    // viewport instead.
+    let scaled = Transform::new().scale(scale as f32, scale as f32)?;
+    let texture = renderer.render_texture(
+        &TransformNode::new(node, &scaled),
+        Some(&Rect::new(
+            viewport.x() * scale as f32,
+            viewport.y() * scale as f32,
+            width as f32,
+            height as f32,
+        )),
+    );
+    renderer.unrealize();
+
+    let stride = width as usize * 4;
+    let mut data = vec![0; stride * height as usize];
+    texture?.download(&mut data, stride);
+    Some(Image {
+        width,
+        height,
+        stride,
+        data,
+    })
+}
+
+// rustdoc-stripper-ignore-next
+/// Like [`render`], but always draws with cairo and returns the pixels instead
+/// of a texture, so it can be called from any thread.
+pub fn render_image(
+    node: &RenderNode,
+    viewport: Option<&Rect>,
+    scale: f64,
+) -> Result<Image, glib::BoolError> {
+    let (viewport, width, height) = image_size(node, viewport, scale)?;
+    let mut surface = cairo::ImageSurface::create(cairo::Format::ARgb32, width, height)
+        .map_err(|err| glib::bool_error!("Failed to create a cairo surface: {}", err))?;
+    {
+        let cr = cairo::Context::new(&surface)
+            .map_err(|err| glib::bool_error!("Failed to create a cairo context: {}", err))?;
+        cr.scale(scale, scale);
+        cr.translate(-viewport.x() as f64, -viewport.y() as f64);
+        node.draw(&cr);
+    }
+    surface.flush();
+
+    let stride = surface.stride() as usize;
+    let data = surface
+        .data()
+        .map_err(|err| glib::bool_error!("Failed to read the cairo surface: {}", err))?
+        .to_vec();
+    Ok(Image {
+        width,
+        height,
+        stride,
+        data,
+    })
+}
+
+// rustdoc-stripper-ignore-next
+/// Deserializes a node from the output of [`RenderNode::serialize`](crate::RenderNode::serialize)
+/// and renders it like [`render_image`].
+///
+/// The node only lives on the calling thread, and deserializing it doesn't
+/// touch any state shared with the main thread: textures are immutable and
+/// can be created on any thread, and text is laid out with the font map that
+/// pangocairo keeps for each thread. Fonts and images referenced by the
+/// serialized data are thus loaded again on every thread that calls this.
+pub fn render_serialized(
+    data: &[u8],
+    viewport: Option<&Rect>,
+    scale: f64,
+) -> Result<Image, glib::BoolError> {
+    let node = RenderNode::deserialize(&glib::Bytes::from(data))
+        .ok_or_else(|| glib::bool_error!("Failed to deserialize the render node"))?;
+    render_image(&node, viewport, scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = r#"
+text {
+  color: rgb(0,0,0);
+  font: "Sans 15px";
+  glyphs: "Hello, world";
+  offset: 0 15;
+}
+"#;
+
+    fn is_blank(image: &Image) -> bool {
+        image.data().iter().all(|byte| *byte == 0)
+    }
+
+    #[test]
+    fn test_render_serialized_threads() {
+        let viewport = Rect::new(0.0, 0.0, 100.0, 20.0);
+        let expected = render_serialized(TEXT.as_bytes(), Some(&viewport), 1.0).unwrap();
+        assert_eq!((expected.width(), expected.height()), (100, 20));
+        assert!(!is_blank(&expected));
+
+        let workers = (0..4)
+            .map(|_| {
+                std::thread::spawn(move || {
+                    render_serialized(TEXT.as_bytes(), Some(&viewport), 1.0).unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            assert_eq!(worker.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_render_serialized_invalid() {
+        assert!(render_serialized(b"not a node", None, 1.0).is_err());
+        assert!(render_serialized(TEXT.as_bytes(), None, 0.0).is_err());
+    }
+}
@@ -156,6 +156,11 @@ pub unsafe trait IsRenderNode:
     fn upcast_ref(&self) -> &RenderNode;
 }
 
+// Downcasts `node` to the type matching its already checked node type.
+pub(crate) fn downcast<T: IsRenderNode>(node: &RenderNode) -> &T {
+    node.downcast_ref::<T>().unwrap()
+}
+
 impl fmt::Display for RenderNode {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str("RenderNode")
@@ -0,0 +1,548 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::render_node::downcast;
+use crate::{
+    BlurNode, BorderNode, ClipNode, ColorNode, ColorStop, ConicGradientNode, CrossFadeNode,
+    DebugNode, InsetShadowNode, LinearGradientNode, NodePath, OpacityNode, OutsetShadowNode,
+    RadialGradientNode, RenderNode, RenderNodeType, RoundedClipNode, ShadowNode, TextNode,
+    TextureNode, Transform, TransformNode,
+};
+use std::fmt;
+
+// rustdoc-stripper-ignore-next
+/// A single difference between two trees of [`RenderNode`](crate::RenderNode)s,
+/// as returned by [`RenderNode::diff`](crate::RenderNode::diff).
+///
+/// The [`Display`](std::fmt::Display) implementation gives a one line, human
+/// readable description of the change.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum NodeChange {
+    Added {
+        path: NodePath,
+        node_type: RenderNodeType,
+    },
+    Removed {
+        path: NodePath,
+        node_type: RenderNodeType,
+    },
+    NodeTypeChanged {
+        path: NodePath,
+        old: RenderNodeType,
+        new: RenderNodeType,
+    },
+    BoundsChanged {
+        path: NodePath,
+        old: graphene::Rect,
+        new: graphene::Rect,
+    },
+    ColorChanged {
+        path: NodePath,
+        old: Option<gdk::RGBA>,
+        new: Option<gdk::RGBA>,
+    },
+    TransformChanged {
+        path: NodePath,
+        old: Option<Transform>,
+        new: Option<Transform>,
+    },
+    // rustdoc-stripper-ignore-next
+    /// The texture of a [`TextureNode`](crate::TextureNode) is a different object.
+    TextureChanged { path: NodePath },
+    // rustdoc-stripper-ignore-next
+    /// Any other node specific property, like the opacity of an
+    /// [`OpacityNode`](crate::OpacityNode).
+    PropertyChanged {
+        path: NodePath,
+        property: &'static str,
+        old: String,
+        new: String,
+    },
+}
+
+impl NodeChange {
+    // rustdoc-stripper-ignore-next
+    /// The path of the changed node. For [`Added`](Self::Added) it is the path in
+    /// the new tree, otherwise the path in the old one.
+    pub fn path(&self) -> &NodePath {
+        match self {
+            Self::Added { path, .. }
+            | Self::Removed { path, .. }
+            | Self::NodeTypeChanged { path, .. }
+            | Self::BoundsChanged { path, .. }
+            | Self::ColorChanged { path, .. }
+            | Self::TransformChanged { path, .. }
+            | Self::TextureChanged { path }
+            | Self::PropertyChanged { path, .. } => path,
+        }
+    }
+}
+
+struct DisplayRect<'a>(&'a graphene::Rect);
+
+impl fmt::Display for DisplayRect<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.0.x(),
+            self.0.y(),
+            self.0.width(),
+            self.0.height()
+        )
+    }
+}
+
+fn display_option<T: fmt::Display>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "none".to_owned(),
+    }
+}
+
+impl fmt::Display for NodeChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Added { path, node_type } => write!(f, "{}: added {}", path, node_type),
+            Self::Removed { path, node_type } => write!(f, "{}: removed {}", path, node_type),
+            Self::NodeTypeChanged { path, old, new } => {
+                write!(f, "{}: replaced {} by {}", path, old, new)
+            }
+            Self::BoundsChanged { path, old, new } => write!(
+                f,
+                "{}: bounds changed from [{}] to [{}]",
+                path,
+                DisplayRect(old),
+                DisplayRect(new)
+            ),
+            Self::ColorChanged { path, old, new } => write!(
+                f,
+                "{}: color changed from {} to {}",
+                path,
+                display_option(old),
+                display_option(new)
+            ),
+            Self::TransformChanged { path, old, new } => write!(
+                f,
+                "{}: transform changed from {} to {}",
+                path,
+                display_option(old),
+                display_option(new)
+            ),
+            Self::TextureChanged { path } => write!(f, "{}: texture changed", path),
+            Self::PropertyChanged {
+                path,
+                property,
+                old,
+                new,
+            } => write!(f, "{}: {} changed from {} to {}", path, property, old, new),
+        }
+    }
+}
+
+impl RenderNode {
+    // rustdoc-stripper-ignore-next
+    /// Compares this tree of nodes with `other`, and returns the changes needed
+    /// to go from this tree to `other`, in depth-first order.
+    ///
+    /// Children are matched by index; when the node types at the same position
+    /// differ, the whole subtree is reported as replaced. Textures are compared
+    /// by identity, not by content.
+    pub fn diff(&self, other: &RenderNode) -> Vec<NodeChange> {
+        let mut changes = Vec::new();
+        diff_node(self, other, NodePath::root(), &mut changes);
+        changes
+    }
+}
+
+fn diff_property<T: PartialEq + fmt::Display>(
+    changes: &mut Vec<NodeChange>,
+    path: &NodePath,
+    property: &'static str,
+    old: T,
+    new: T,
+) {
+    if old != new {
+        changes.push(NodeChange::PropertyChanged {
+            path: path.clone(),
+            property,
+            old: old.to_string(),
+            new: new.to_string(),
+        });
+    }
+}
+
+fn diff_color(
+    changes: &mut Vec<NodeChange>,
+    path: &NodePath,
+    old: Option<gdk::RGBA>,
+    new: Option<gdk::RGBA>,
+) {
+    if old != new {
+        changes.push(NodeChange::ColorChanged {
+            path: path.clone(),
+            old,
+            new,
+        });
+    }
+}
+
+fn diff_color_stops(
+    changes: &mut Vec<NodeChange>,
+    path: &NodePath,
+    old: &[ColorStop],
+    new: &[ColorStop],
+) {
+    diff_property(changes, path, "color stops", old.len(), new.len());
+    for (idx, (a, b)) in old.iter().zip(new).enumerate() {
+        if a.offset() != b.offset() || a.color() != b.color() {
+            changes.push(NodeChange::PropertyChanged {
+                path: path.clone(),
+                property: "color stop",
+                old: format!("{}: {} {}", idx, a.offset(), a.color()),
+                new: format!("{}: {} {}", idx, b.offset(), b.color()),
+            });
+        }
+    }
+}
+
+// The color, offset, spread and blur radius of an inset or outset shadow.
+type BoxShadow = (Option<gdk::RGBA>, (f32, f32), f32, f32);
+
+fn diff_box_shadow(changes: &mut Vec<NodeChange>, path: &NodePath, old: BoxShadow, new: BoxShadow) {
+    diff_color(changes, path, old.0, new.0);
+    diff_property(changes, path, "dx", old.1 .0, new.1 .0);
+    diff_property(changes, path, "dy", old.1 .1, new.1 .1);
+    diff_property(changes, path, "spread", old.2, new.2);
+    diff_property(changes, path, "blur radius", old.3, new.3);
+}
+
+fn diff_node(old: &RenderNode, new: &RenderNode, path: NodePath, changes: &mut Vec<NodeChange>) {
+    if old.node_type() != new.node_type() {
+        changes.push(NodeChange::NodeTypeChanged {
+            path,
+            old: old.node_type(),
+            new: new.node_type(),
+        });
+        return;
+    }
+
+    let (old_bounds, new_bounds) = (old.bounds(), new.bounds());
+    if old_bounds != new_bounds {
+        changes.push(NodeChange::BoundsChanged {
+            path: path.clone(),
+            old: old_bounds,
+            new: new_bounds,
+        });
+    }
+
+    match old.node_type() {
+        RenderNodeType::ColorNode => {
+            let (a, b) = (downcast::<ColorNode>(old), downcast::<ColorNode>(new));
+            diff_color(changes, &path, a.color(), b.color());
+        }
+        RenderNodeType::TextNode => {
+            let (a, b) = (downcast::<TextNode>(old), downcast::<TextNode>(new));
+            diff_color(changes, &path, a.color(), b.color());
+            diff_property(changes, &path, "glyphs", a.num_glyphs(), b.num_glyphs());
+        }
+        RenderNodeType::BorderNode => {
+            let (a, b) = (downcast::<BorderNode>(old), downcast::<BorderNode>(new));
+            let sides = ["top color", "right color", "bottom color", "left color"];
+            for ((side, old_color), new_color) in sides.into_iter().zip(a.colors()).zip(b.colors())
+            {
+                diff_property(changes, &path, side, old_color, new_color);
+            }
+        }
+        RenderNodeType::LinearGradientNode => diff_color_stops(
+            changes,
+            &path,
+            &downcast::<LinearGradientNode>(old).color_stops(),
+            &downcast::<LinearGradientNode>(new).color_stops(),
+        ),
+        RenderNodeType::RadialGradientNode => diff_color_stops(
+            changes,
+            &path,
+            &downcast::<RadialGradientNode>(old).color_stops(),
+            &downcast::<RadialGradientNode>(new).color_stops(),
+        ),
+        RenderNodeType::ConicGradientNode => diff_color_stops(
+            changes,
+            &path,
+            &downcast::<ConicGradientNode>(old).color_stops(),
+            &downcast::<ConicGradientNode>(new).color_stops(),
+        ),
+        RenderNodeType::InsetShadowNode => {
+            let shadow = |node: &RenderNode| {
+                let node = downcast::<InsetShadowNode>(node);
+                (
+                    node.color(),
+                    (node.dx(), node.dy()),
+                    node.spread(),
+                    node.blur_radius(),
+                )
+            };
+            diff_box_shadow(changes, &path, shadow(old), shadow(new));
+        }
+        RenderNodeType::OutsetShadowNode => {
+            let shadow = |node: &RenderNode| {
+                let node = downcast::<OutsetShadowNode>(node);
+                (
+                    node.color(),
+                    (node.dx(), node.dy()),
+                    node.spread(),
+                    node.blur_radius(),
+                )
+            };
+            diff_box_shadow(changes, &path, shadow(old), shadow(new));
+        }
+        RenderNodeType::ShadowNode => {
+            let (a, b) = (downcast::<ShadowNode>(old), downcast::<ShadowNode>(new));
+            diff_property(changes, &path, "shadows", a.n_shadows(), b.n_shadows());
+            for (old_shadow, new_shadow) in (0..a.n_shadows().min(b.n_shadows()))
+                .filter_map(|i| Some((a.shadow(i)?, b.shadow(i)?)))
+            {
+                diff_box_shadow(
+                    changes,
+                    &path,
+                    (
+                        Some(old_shadow.color()),
+                        (old_shadow.dx(), old_shadow.dy()),
+                        0.0,
+                        old_shadow.radius(),
+                    ),
+                    (
+                        Some(new_shadow.color()),
+                        (new_shadow.dx(), new_shadow.dy()),
+                        0.0,
+                        new_shadow.radius(),
+                    ),
+                );
+            }
+        }
+        RenderNodeType::RoundedClipNode => {
+            let (a, b) = (
+                downcast::<RoundedClipNode>(old).clip(),
+                downcast::<RoundedClipNode>(new).clip(),
+            );
+            if a != b {
+                diff_property(
+                    changes,
+                    &path,
+                    "clip",
+                    format!("{:?}", a),
+                    format!("{:?}", b),
+                );
+            }
+        }
+        RenderNodeType::TransformNode => {
+            let (old_transform, new_transform) = (
+                downcast::<TransformNode>(old).transform(),
+                downcast::<TransformNode>(new).transform(),
+            );
+            if old_transform != new_transform {
+                changes.push(NodeChange::TransformChanged {
+                    path: path.clone(),
+                    old: old_transform,
+                    new: new_transform,
+                });
+            }
+        }
+        RenderNodeType::TextureNode => {
+            if downcast::<TextureNode>(old).texture() != downcast::<TextureNode>(new).texture() {
+                changes.push(NodeChange::TextureChanged { path: path.clone() });
+            }
+        }
+        RenderNodeType::OpacityNode => diff_property(
+            changes,
+            &path,
+            "opacity",
+            downcast::<OpacityNode>(old).opacity(),
+            downcast::<OpacityNode>(new).opacity(),
+        ),
+        RenderNodeType::ClipNode => {
+            let (a, b) = (
+                downcast::<ClipNode>(old).clip(),
+                downcast::<ClipNode>(new).clip(),
+            );
+            if a != b {
+                diff_property(
+                    changes,
+                    &path,
+                    "clip",
+                    display_option(&a.as_ref().map(DisplayRect)),
+                    display_option(&b.as_ref().map(DisplayRect)),
+                );
+            }
+        }
+        RenderNodeType::BlurNode => diff_property(
+            changes,
+            &path,
+            "radius",
+            downcast::<BlurNode>(old).radius(),
+            downcast::<BlurNode>(new).radius(),
+        ),
+        RenderNodeType::CrossFadeNode => diff_property(
+            changes,
+            &path,
+            "progress",
+            downcast::<CrossFadeNode>(old).progress(),
+            downcast::<CrossFadeNode>(new).progress(),
+        ),
+        RenderNodeType::DebugNode => diff_property(
+            changes,
+            &path,
+            "message",
+            display_option(&downcast::<DebugNode>(old).message()),
+            display_option(&downcast::<DebugNode>(new).message()),
+        ),
+        _ => (),
+    }
+
+    let (old_children, new_children) = (old.children(), new.children());
+    for (idx, (a, b)) in old_children.iter().zip(&new_children).enumerate() {
+        diff_node(a, b, path.child(idx), changes);
+    }
+    for (idx, child) in old_children.iter().enumerate().skip(new_children.len()) {
+        changes.push(NodeChange::Removed {
+            path: path.child(idx),
+            node_type: child.node_type(),
+        });
+    }
+    for (idx, child) in new_children.iter().enumerate().skip(old_children.len()) {
+        changes.push(NodeChange::Added {
+            path: path.child(idx),
+            node_type: child.node_type(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::RoundedRect;
+    use graphene::{Point, Rect};
+
+    fn gradient(stops: &[(f32, gdk::RGBA)]) -> RenderNode {
+        let stops = stops
+            .iter()
+            .map(|(offset, color)| ColorStop::new(*offset, *color))
+            .collect::<Vec<_>>();
+        LinearGradientNode::new(
+            &Rect::new(0.0, 0.0, 10.0, 10.0),
+            &Point::new(0.0, 0.0),
+            &Point::new(10.0, 0.0),
+            &stops,
+        )
+        .upcast()
+    }
+
+    fn border(colors: [gdk::RGBA; 4]) -> RenderNode {
+        BorderNode::new(
+            &RoundedRect::from_rect(Rect::new(0.0, 0.0, 10.0, 10.0), 0.0),
+            &[1.0; 4],
+            &colors,
+        )
+        .upcast()
+    }
+
+    #[test]
+    fn test_diff_identical() {
+        let node = gradient(&[(0.0, gdk::RGBA::RED), (1.0, gdk::RGBA::BLUE)]);
+        assert!(node.diff(&node).is_empty());
+    }
+
+    #[test]
+    fn test_diff_color_stops() {
+        let old = gradient(&[
+            (0.0, gdk::RGBA::RED),
+            (0.5, gdk::RGBA::GREEN),
+            (1.0, gdk::RGBA::BLUE),
+        ]);
+        let new = gradient(&[
+            (0.0, gdk::RGBA::BLACK),
+            (0.5, gdk::RGBA::GREEN),
+            (0.75, gdk::RGBA::BLUE),
+        ]);
+        let changes = old.diff(&new);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            changes[0],
+            NodeChange::PropertyChanged {
+                path: NodePath::root(),
+                property: "color stop",
+                old: format!("0: 0 {}", gdk::RGBA::RED),
+                new: format!("0: 0 {}", gdk::RGBA::BLACK),
+            }
+        );
+        assert_eq!(
+            changes[1],
+            NodeChange::PropertyChanged {
+                path: NodePath::root(),
+                property: "color stop",
+                old: format!("2: 1 {}", gdk::RGBA::BLUE),
+                new: format!("2: 0.75 {}", gdk::RGBA::BLUE),
+            }
+        );
+
+        let shorter = gradient(&[(0.0, gdk::RGBA::RED), (0.5, gdk::RGBA::GREEN)]);
+        let changes = old.diff(&shorter);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].to_string(), "/: color stops changed from 3 to 2");
+    }
+
+    #[test]
+    fn test_diff_border_colors() {
+        let old = border([gdk::RGBA::RED; 4]);
+        let new = border([
+            gdk::RGBA::BLUE,
+            gdk::RGBA::RED,
+            gdk::RGBA::GREEN,
+            gdk::RGBA::RED,
+        ]);
+        let properties = old
+            .diff(&new)
+            .iter()
+            .map(|change| match change {
+                NodeChange::PropertyChanged { property, .. } => *property,
+                change => panic!("unexpected change {}", change),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(properties, ["top color", "bottom color"]);
+    }
+
+    #[test]
+    fn test_diff_children() {
+        let red = ColorNode::new(&gdk::RGBA::RED, &Rect::new(0.0, 0.0, 10.0, 10.0)).upcast();
+        let blue = ColorNode::new(&gdk::RGBA::BLUE, &Rect::new(0.0, 0.0, 10.0, 10.0)).upcast();
+        let opacity = OpacityNode::new(&red, 0.5).upcast();
+
+        let old = crate::ContainerNode::new(&[red.clone(), red.clone()]).upcast();
+        let new = crate::ContainerNode::new(&[blue, opacity, red]).upcast();
+        let changes = old.diff(&new);
+        assert_eq!(
+            changes,
+            [
+                NodeChange::ColorChanged {
+                    path: NodePath::from(vec![0]),
+                    old: Some(gdk::RGBA::RED),
+                    new: Some(gdk::RGBA::BLUE),
+                },
+                NodeChange::NodeTypeChanged {
+                    path: NodePath::from(vec![1]),
+                    old: RenderNodeType::ColorNode,
+                    new: RenderNodeType::OpacityNode,
+                },
+                NodeChange::Added {
+                    path: NodePath::from(vec![2]),
+                    node_type: RenderNodeType::ColorNode,
+                },
+            ]
+        );
+        assert_eq!(
+            changes[2].to_string(),
+            "/2: added RenderNodeType::ColorNode"
+        );
+    }
+}
@@ -0,0 +1,274 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::render_node::downcast;
+use crate::{
+    BorderNode, ClipNode, ColorNode, NodePath, OpacityNode, RenderNode, RenderNodeType,
+    RoundedClipNode, TextNode, TransformCategory, TransformNode,
+};
+use std::fmt;
+
+// rustdoc-stripper-ignore-next
+/// The kind of problem reported by a [`NodeLint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum NodeLintKind {
+    // rustdoc-stripper-ignore-next
+    /// The node draws nothing, because it is empty, fully transparent or has no
+    /// visible content.
+    Invisible,
+    // rustdoc-stripper-ignore-next
+    /// The clip of the node does not intersect its child, so the whole subtree
+    /// is clipped away.
+    FullyClipped,
+    // rustdoc-stripper-ignore-next
+    /// An [`OpacityNode`](crate::OpacityNode) that is fully opaque.
+    RedundantOpacity,
+    // rustdoc-stripper-ignore-next
+    /// A [`TransformNode`](crate::TransformNode) with the identity transform.
+    RedundantTransform,
+}
+
+impl fmt::Display for NodeLintKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Invisible => "node is invisible",
+            Self::FullyClipped => "subtree is fully clipped",
+            Self::RedundantOpacity => "opacity node has no effect",
+            Self::RedundantTransform => "transform node has no effect",
+        })
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// A problem found in a tree of nodes by [`RenderNode::lint`](crate::RenderNode::lint).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NodeLint {
+    pub path: NodePath,
+    pub kind: NodeLintKind,
+}
+
+impl fmt::Display for NodeLint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+impl RenderNode {
+    // rustdoc-stripper-ignore-next
+    /// Looks for nodes that could be removed from this tree without changing
+    /// what it draws, in depth-first order.
+    ///
+    /// The subtree of an invisible or fully clipped node is not inspected any
+    /// further.
+    pub fn lint(&self) -> Vec<NodeLint> {
+        let mut lints = Vec::new();
+        lint_node(self, NodePath::root(), &mut lints);
+        lints
+    }
+}
+
+fn is_transparent(color: Option<gdk::RGBA>) -> bool {
+    color.map_or(true, |color| color.alpha() <= 0.0)
+}
+
+// Whether the clip of a clip or rounded clip node misses its child.
+fn is_fully_clipped(node: &RenderNode, children: &[RenderNode]) -> bool {
+    let child = match children.first() {
+        Some(child) => child,
+        None => return false,
+    };
+    match node.node_type() {
+        RenderNodeType::ClipNode => downcast::<ClipNode>(node)
+            .clip()
+            .map_or(false, |clip| clip.intersection(&child.bounds()).is_none()),
+        RenderNodeType::RoundedClipNode => downcast::<RoundedClipNode>(node)
+            .clip()
+            .map_or(false, |clip| !clip.intersects_rect(child.bounds())),
+        _ => false,
+    }
+}
+
+fn lint_kind(node: &RenderNode) -> Option<NodeLintKind> {
+    // The bounds of a clip node are the part of its child it lets through, so
+    // they are empty when the clip misses the child.
+    let children = node.children();
+    if is_fully_clipped(node, &children) {
+        return Some(NodeLintKind::FullyClipped);
+    }
+
+    let bounds = node.bounds();
+    if bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+        return Some(NodeLintKind::Invisible);
+    }
+
+    match node.node_type() {
+        RenderNodeType::ContainerNode if children.is_empty() => Some(NodeLintKind::Invisible),
+        RenderNodeType::ColorNode => {
+            is_transparent(downcast::<ColorNode>(node).color()).then(|| NodeLintKind::Invisible)
+        }
+        RenderNodeType::TextNode => {
+            let node = downcast::<TextNode>(node);
+            (node.num_glyphs() == 0 || is_transparent(node.color()))
+                .then(|| NodeLintKind::Invisible)
+        }
+        RenderNodeType::BorderNode => {
+            let node = downcast::<BorderNode>(node);
+            let visible = node
+                .widths()
+                .iter()
+                .zip(node.colors())
+                .any(|(width, color)| *width > 0.0 && color.alpha() > 0.0);
+            (!visible).then(|| NodeLintKind::Invisible)
+        }
+        RenderNodeType::OpacityNode => {
+            let opacity = downcast::<OpacityNode>(node).opacity();
+            if opacity <= 0.0 {
+                Some(NodeLintKind::Invisible)
+            } else if opacity >= 1.0 {
+                Some(NodeLintKind::RedundantOpacity)
+            } else {
+                None
+            }
+        }
+        RenderNodeType::TransformNode => {
+            let redundant = downcast::<TransformNode>(node)
+                .transform()
+                .map_or(true, |transform| {
+                    transform.category() == TransformCategory::Identity
+                });
+            redundant.then(|| NodeLintKind::RedundantTransform)
+        }
+        _ => None,
+    }
+}
+
+fn lint_node(node: &RenderNode, path: NodePath, lints: &mut Vec<NodeLint>) {
+    let kind = lint_kind(node);
+    if let Some(kind) = kind {
+        lints.push(NodeLint {
+            path: path.clone(),
+            kind,
+        });
+    }
+    if matches!(
+        kind,
+        Some(NodeLintKind::Invisible) | Some(NodeLintKind::FullyClipped)
+    ) {
+        return;
+    }
+
+    for (idx, child) in node.children().iter().enumerate() {
+        lint_node(child, path.child(idx), lints);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::{ContainerNode, RoundedRect, Transform};
+    use graphene::Rect;
+
+    fn square() -> RenderNode {
+        ColorNode::new(&gdk::RGBA::RED, &Rect::new(0.0, 0.0, 10.0, 10.0)).upcast()
+    }
+
+    fn lints(node: &impl IsRenderNode) -> Vec<(NodePath, NodeLintKind)> {
+        node.upcast_ref()
+            .lint()
+            .into_iter()
+            .map(|lint| (lint.path, lint.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_clean() {
+        let node = ContainerNode::new(&[
+            square(),
+            OpacityNode::new(&square(), 0.5).upcast(),
+            ClipNode::new(&square(), &Rect::new(5.0, 5.0, 10.0, 10.0)).upcast(),
+        ]);
+        assert!(lints(&node).is_empty());
+    }
+
+    #[test]
+    fn test_lint_invisible() {
+        let transparent = ColorNode::new(
+            &gdk::RGBA::new(1.0, 0.0, 0.0, 0.0),
+            &Rect::new(0.0, 0.0, 10.0, 10.0),
+        );
+        let empty = ColorNode::new(&gdk::RGBA::RED, &Rect::new(0.0, 0.0, 0.0, 10.0));
+        let border = BorderNode::new(
+            &RoundedRect::from_rect(Rect::new(0.0, 0.0, 10.0, 10.0), 0.0),
+            &[0.0; 4],
+            &[gdk::RGBA::RED; 4],
+        );
+        // The children of an invisible node are not inspected.
+        let hidden = OpacityNode::new(&transparent, 0.0);
+        let node = ContainerNode::new(&[
+            transparent.upcast(),
+            empty.upcast(),
+            border.upcast(),
+            hidden.upcast(),
+            ContainerNode::new(&[]).upcast(),
+        ]);
+        assert_eq!(
+            lints(&node),
+            (0..5)
+                .map(|idx| (NodePath::from(vec![idx]), NodeLintKind::Invisible))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_lint_fully_clipped() {
+        let clip = ClipNode::new(&square(), &Rect::new(20.0, 20.0, 10.0, 10.0));
+        assert_eq!(
+            lints(&clip),
+            [(NodePath::root(), NodeLintKind::FullyClipped)]
+        );
+
+        // The bounds intersect, but the square is outside of the rounded corner.
+        let corner = ColorNode::new(&gdk::RGBA::RED, &Rect::new(0.0, 0.0, 2.0, 2.0));
+        let clip = RoundedClipNode::new(
+            &corner,
+            &RoundedRect::from_rect(Rect::new(0.0, 0.0, 20.0, 20.0), 10.0),
+        );
+        assert_eq!(
+            lints(&clip),
+            [(NodePath::root(), NodeLintKind::FullyClipped)]
+        );
+    }
+
+    #[test]
+    fn test_lint_redundant_opacity() {
+        let transparent = ColorNode::new(
+            &gdk::RGBA::new(1.0, 0.0, 0.0, 0.0),
+            &Rect::new(0.0, 0.0, 10.0, 10.0),
+        );
+        let opacity = OpacityNode::new(&transparent, 1.0);
+        assert_eq!(
+            lints(&opacity),
+            [
+                (NodePath::root(), NodeLintKind::RedundantOpacity),
+                (NodePath::from(vec![0]), NodeLintKind::Invisible),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_redundant_transform() {
+        let identity = TransformNode::new(&square(), &Transform::new());
+        assert_eq!(
+            lints(&identity),
+            [(NodePath::root(), NodeLintKind::RedundantTransform)]
+        );
+        let translate = TransformNode::new(
+            &square(),
+            &Transform::new()
+                .translate(&graphene::Point::new(1.0, 0.0))
+                .unwrap(),
+        );
+        assert!(lints(&translate).is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::{ParseLocation, RenderNode};
+use std::fmt;
+use std::fmt::Write;
+
+// rustdoc-stripper-ignore-next
+/// A position in the source of a render node, as reported by
+/// [`RenderNode::parse`](crate::RenderNode::parse).
+///
+/// Lines and columns start at 0, but are displayed starting at 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourcePosition {
+    // rustdoc-stripper-ignore-next
+    /// Offset in bytes from the start of the source.
+    pub offset: usize,
+    pub line: usize,
+    // rustdoc-stripper-ignore-next
+    /// Offset in characters from the start of the line.
+    pub column: usize,
+}
+
+impl From<&ParseLocation> for SourcePosition {
+    fn from(location: &ParseLocation) -> Self {
+        Self {
+            offset: location.bytes(),
+            line: location.lines(),
+            column: location.line_chars(),
+        }
+    }
+}
+
+impl fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line + 1, self.column + 1)
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// An error found while parsing a render node with
+/// [`RenderNode::parse`](crate::RenderNode::parse).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeParseError {
+    start: SourcePosition,
+    end: SourcePosition,
+    message: String,
+}
+
+impl NodeParseError {
+    pub fn start(&self) -> SourcePosition {
+        self.start
+    }
+
+    pub fn end(&self) -> SourcePosition {
+        self.end
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Renders the error together with the offending line of `source`, which
+    /// has to be the string that was parsed.
+    ///
+    /// ```text
+    /// error: Expected a color
+    ///  --> 3:10
+    ///   |
+    /// 3 |   color: rdd;
+    ///   |          ^^^
+    /// ```
+    pub fn render(&self, source: &str) -> String {
+        let mut out = format!("error: {}\n", self.message);
+        let line = match source.lines().nth(self.start.line) {
+            Some(line) => line,
+            None => {
+                writeln!(out, " --> {}", self.start).unwrap();
+                return out;
+            }
+        };
+
+        let number = (self.start.line + 1).to_string();
+        let gutter = " ".repeat(number.len());
+        let line_len = line.chars().count();
+        let start = self.start.column.min(line_len);
+        let end = if self.end.line == self.start.line {
+            self.end.column.min(line_len)
+        } else {
+            line_len
+        };
+        let width = end.saturating_sub(start).max(1);
+
+        writeln!(out, "{} --> {}", gutter, self.start).unwrap();
+        writeln!(out, "{} |", gutter).unwrap();
+        writeln!(out, "{} | {}", number, line).unwrap();
+        writeln!(
+            out,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(start),
+            "^".repeat(width)
+        )
+        .unwrap();
+        out
+    }
+}
+
+impl fmt::Display for NodeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.start, self.message)
+    }
+}
+
+impl std::error::Error for NodeParseError {}
+
+impl RenderNode {
+    // rustdoc-stripper-ignore-next
+    /// Parses a render node from the text format produced by
+    /// [`serialize`](Self::serialize).
+    ///
+    /// Unlike [`deserialize`](Self::deserialize), any error makes the parsing
+    /// fail, and all the errors are returned with their position in `source`.
+    ///
+    /// ```
+    /// use gsk4 as gsk;
+    ///
+    /// let source = "color {\n  bounds: 0 0 10 10;\n  color: rdd;\n}\n";
+    /// let errors = gsk::RenderNode::parse(source).unwrap_err();
+    /// eprintln!("{}", errors[0].render(source));
+    /// ```
+    pub fn parse(source: &str) -> Result<RenderNode, Vec<NodeParseError>> {
+        let bytes = glib::Bytes::from(source.as_bytes());
+        let mut errors = Vec::new();
+        let node = Self::deserialize_with_error_func(&bytes, |start, end, error| {
+            errors.push(NodeParseError {
+                start: start.into(),
+                end: end.into(),
+                message: error.message().to_owned(),
+            });
+        });
+        match node {
+            Some(node) if errors.is_empty() => Ok(node),
+            _ => {
+                if errors.is_empty() {
+                    errors.push(NodeParseError {
+                        start: SourcePosition::default(),
+                        end: SourcePosition::default(),
+                        message: "Failed to parse the render node".to_owned(),
+                    });
+                }
+                Err(errors)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "color {\n  bounds: 0 0 10 10;\n  color: rdd;\n}\n";
+
+    fn position(offset: usize, line: usize, column: usize) -> SourcePosition {
+        SourcePosition {
+            offset,
+            line,
+            column,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let node = RenderNode::parse("color {\n  bounds: 0 0 10 10;\n  color: red;\n}\n").unwrap();
+        assert_eq!(node.node_type(), crate::RenderNodeType::ColorNode);
+    }
+
+    #[test]
+    fn test_parse_error_position() {
+        let errors = RenderNode::parse(SOURCE).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].start(), position(38, 2, 9));
+        assert_eq!(errors[0].end(), position(41, 2, 12));
+        assert!(errors[0].message().contains("rdd"));
+        assert!(errors[0].to_string().starts_with("3:10: "));
+    }
+
+    #[test]
+    fn test_render() {
+        let error = NodeParseError {
+            start: position(38, 2, 9),
+            end: position(41, 2, 12),
+            message: "Expected a color".to_owned(),
+        };
+        assert_eq!(
+            error.render(SOURCE),
+            "error: Expected a color\n  --> 3:10\n  |\n3 |   color: rdd;\n  |          ^^^\n"
+        );
+
+        // Errors spanning several lines are underlined to the end of the first one.
+        let error = NodeParseError {
+            start: position(31, 1, 2),
+            end: position(41, 2, 12),
+            message: "Unexpected".to_owned(),
+        };
+        assert_eq!(
+            error.render(SOURCE),
+            "error: Unexpected\n  --> 2:3\n  |\n2 |   bounds: 0 0 10 10;\n  |   ^^^^^^^^^^^^^^^^^^\n"
+        );
+
+        // Empty ranges still get a marker, and positions past the end of the
+        // source are only printed.
+        let error = NodeParseError {
+            start: position(51, 4, 0),
+            end: position(51, 4, 0),
+            message: "Unexpected end".to_owned(),
+        };
+        assert_eq!(error.render(SOURCE), "error: Unexpected end\n --> 5:1\n");
+        let error = NodeParseError {
+            start: position(8, 1, 0),
+            end: position(8, 1, 0),
+            message: "Empty".to_owned(),
+        };
+        assert!(error.render(SOURCE).ends_with("\n  | ^\n"));
+    }
+}
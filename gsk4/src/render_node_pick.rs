@@ -0,0 +1,220 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::render_node::downcast;
+use crate::{
+    BorderNode, ClipNode, ColorNode, DebugNode, NodePath, OpacityNode, RenderNode, RenderNodeType,
+    RepeatNode, RoundedClipNode, TransformNode,
+};
+use graphene::Point;
+
+impl RenderNode {
+    // rustdoc-stripper-ignore-next
+    /// Returns the paths of the nodes drawing at `point`, from the topmost one
+    /// down.
+    ///
+    /// Only nodes that draw something themselves are returned, not the
+    /// containers and effects wrapping them. Transforms, clips and rounded
+    /// clips are taken into account, and fully transparent nodes and subtrees
+    /// are skipped.
+    pub fn pick(&self, point: &Point) -> Vec<NodePath> {
+        let mut hits = Vec::new();
+        pick_node(self, *point, NodePath::root(), &mut hits);
+        hits
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns the messages of the [`DebugNode`](crate::DebugNode)s wrapping the
+    /// topmost node at `point`, from the innermost one out.
+    ///
+    /// Widgets can wrap parts of their snapshot in debug nodes to tag them, for
+    /// example with the identifier of the model object they represent, and map
+    /// a click back to that object.
+    pub fn pick_tags(&self, point: &Point) -> Vec<String> {
+        let path = match self.pick(point).into_iter().next() {
+            Some(path) => path,
+            None => return Vec::new(),
+        };
+        let mut tags = Vec::new();
+        let mut node = self.clone();
+        for &idx in path.indices() {
+            if let Some(message) = node.downcast_ref::<DebugNode>().and_then(|n| n.message()) {
+                tags.push(message.to_string());
+            }
+            node = match node.children().into_iter().nth(idx) {
+                Some(child) => child,
+                None => break,
+            };
+        }
+        tags.reverse();
+        tags
+    }
+}
+
+fn pick_node(node: &RenderNode, point: Point, path: NodePath, hits: &mut Vec<NodePath>) {
+    if !node.bounds().contains_point(&point) {
+        return;
+    }
+
+    let child_point = match node.node_type() {
+        RenderNodeType::TransformNode => {
+            let transform = downcast::<TransformNode>(node).transform();
+            match transform {
+                Some(transform) => match transform.invert() {
+                    Some(inverse) => inverse.transform_point(&point),
+                    // Not invertible, everything is flattened to nothing.
+                    None => return,
+                },
+                None => point,
+            }
+        }
+        RenderNodeType::OpacityNode if downcast::<OpacityNode>(node).opacity() <= 0.0 => return,
+        RenderNodeType::ClipNode => match downcast::<ClipNode>(node).clip() {
+            Some(clip) if !clip.contains_point(&point) => return,
+            _ => point,
+        },
+        RenderNodeType::RoundedClipNode => match downcast::<RoundedClipNode>(node).clip() {
+            Some(clip) if !clip.contains_point(point) => return,
+            _ => point,
+        },
+        RenderNodeType::RepeatNode => {
+            let node = downcast::<RepeatNode>(node);
+            let tile = match node
+                .child_bounds()
+                .or_else(|| node.child().map(|c| c.bounds()))
+            {
+                Some(tile) if tile.width() > 0.0 && tile.height() > 0.0 => tile,
+                _ => return,
+            };
+            Point::new(
+                tile.x() + (point.x() - tile.x()).rem_euclid(tile.width()),
+                tile.y() + (point.y() - tile.y()).rem_euclid(tile.height()),
+            )
+        }
+        _ => point,
+    };
+
+    let children = node.children();
+    if children.is_empty() {
+        if draws_at(node, &point) {
+            hits.push(path);
+        }
+        return;
+    }
+    // Children are drawn in order, so the last one is on top.
+    for (idx, child) in children.iter().enumerate().rev() {
+        pick_node(child, child_point, path.child(idx), hits);
+    }
+}
+
+// Whether a node without children draws at `point`, which is inside its bounds.
+fn draws_at(node: &RenderNode, point: &Point) -> bool {
+    match node.node_type() {
+        RenderNodeType::ColorNode => downcast::<ColorNode>(node)
+            .color()
+            .map_or(false, |color| color.alpha() > 0.0),
+        RenderNodeType::BorderNode => {
+            let node = downcast::<BorderNode>(node);
+            let outline = node.outline();
+            let widths = node.widths();
+            let mut inner = outline.clone();
+            inner.shrink(widths[0], widths[1], widths[2], widths[3]);
+            outline.contains_point(*point) && !inner.contains_point(*point)
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::{ContainerNode, RoundedRect, Transform};
+    use graphene::Rect;
+
+    fn square(x: f32, y: f32) -> RenderNode {
+        ColorNode::new(&gdk::RGBA::RED, &Rect::new(x, y, 10.0, 10.0)).upcast()
+    }
+
+    fn paths(indices: &[&[usize]]) -> Vec<NodePath> {
+        indices
+            .iter()
+            .map(|indices| NodePath::from(indices.to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn test_pick_order() {
+        let node = ContainerNode::new(&[square(0.0, 0.0), square(5.0, 5.0)]).upcast();
+        assert_eq!(node.pick(&Point::new(7.0, 7.0)), paths(&[&[1], &[0]]));
+        assert_eq!(node.pick(&Point::new(2.0, 2.0)), paths(&[&[0]]));
+        assert!(node.pick(&Point::new(20.0, 20.0)).is_empty());
+    }
+
+    #[test]
+    fn test_pick_transform() {
+        let transform = Transform::new()
+            .translate(&Point::new(20.0, 0.0))
+            .unwrap()
+            .scale(2.0, 2.0)
+            .unwrap();
+        let node = TransformNode::new(&square(0.0, 0.0), &transform).upcast();
+        assert_eq!(node.pick(&Point::new(39.0, 19.0)), paths(&[&[0]]));
+        assert!(node.pick(&Point::new(5.0, 5.0)).is_empty());
+    }
+
+    #[test]
+    fn test_pick_clip() {
+        let node = ClipNode::new(&square(0.0, 0.0), &Rect::new(0.0, 0.0, 5.0, 10.0)).upcast();
+        assert_eq!(node.pick(&Point::new(2.0, 5.0)), paths(&[&[0]]));
+        assert!(node.pick(&Point::new(7.0, 5.0)).is_empty());
+
+        let clip = RoundedRect::from_rect(Rect::new(0.0, 0.0, 10.0, 10.0), 5.0);
+        let node = RoundedClipNode::new(&square(0.0, 0.0), &clip).upcast();
+        assert_eq!(node.pick(&Point::new(5.0, 5.0)), paths(&[&[0]]));
+        assert!(node.pick(&Point::new(0.5, 0.5)).is_empty());
+    }
+
+    #[test]
+    fn test_pick_transparent() {
+        let point = Point::new(5.0, 5.0);
+        let node = OpacityNode::new(&square(0.0, 0.0), 0.0).upcast();
+        assert!(node.pick(&point).is_empty());
+        let node = OpacityNode::new(&square(0.0, 0.0), 0.5).upcast();
+        assert_eq!(node.pick(&point), paths(&[&[0]]));
+
+        let transparent = ColorNode::new(
+            &gdk::RGBA::new(1.0, 0.0, 0.0, 0.0),
+            &Rect::new(0.0, 0.0, 10.0, 10.0),
+        )
+        .upcast();
+        let node = ContainerNode::new(&[square(0.0, 0.0), transparent]).upcast();
+        assert_eq!(node.pick(&point), paths(&[&[0]]));
+    }
+
+    #[test]
+    fn test_pick_border() {
+        let border = BorderNode::new(
+            &RoundedRect::from_rect(Rect::new(0.0, 0.0, 10.0, 10.0), 0.0),
+            &[2.0; 4],
+            &[gdk::RGBA::RED; 4],
+        )
+        .upcast();
+        assert_eq!(border.pick(&Point::new(1.0, 5.0)), paths(&[&[]]));
+        assert!(border.pick(&Point::new(5.0, 5.0)).is_empty());
+    }
+
+    #[test]
+    fn test_pick_tags() {
+        let first = DebugNode::new(&square(0.0, 0.0), "first");
+        let second = DebugNode::new(&square(5.0, 5.0), "second");
+        let node = DebugNode::new(
+            &ContainerNode::new(&[first.upcast(), second.upcast()]),
+            "list",
+        )
+        .upcast();
+        assert_eq!(node.pick_tags(&Point::new(7.0, 7.0)), ["second", "list"]);
+        assert_eq!(node.pick_tags(&Point::new(2.0, 2.0)), ["first", "list"]);
+        assert!(node.pick_tags(&Point::new(20.0, 20.0)).is_empty());
+        assert!(square(0.0, 0.0).pick_tags(&Point::new(5.0, 5.0)).is_empty());
+    }
+}
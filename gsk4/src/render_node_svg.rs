@@ -0,0 +1,618 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use crate::render_node::downcast;
+use crate::{
+    BorderNode, ClipNode, ColorNode, ColorStop, ConicGradientNode, LinearGradientNode, OpacityNode,
+    RadialGradientNode, RenderNode, RenderNodeType, RoundedClipNode, RoundedRect, ShadowNode,
+    TextNode, TextureNode, TransformCategory, TransformNode,
+};
+use gdk::RGBA;
+use graphene::{Point, Rect};
+use pangocairo::prelude::FontExt as _;
+use std::fmt::{self, Write as _};
+use std::io::{self, Write};
+
+// Glyphs at or above this value are empty or unknown and have no outline.
+const PANGO_GLYPH_EMPTY: u32 = 0x0FFF_FFFF;
+// SVG has no conic gradients, they are approximated with this many wedges.
+const CONIC_WEDGES: usize = 180;
+
+impl RenderNode {
+    // rustdoc-stripper-ignore-next
+    /// Writes this node as a standalone SVG document to `out`.
+    ///
+    /// Color, border, gradient, clip, rounded clip, transform, opacity, shadow,
+    /// text and texture nodes are translated to SVG elements. Text is written as
+    /// glyph outlines, so the document does not depend on the fonts available
+    /// where it is viewed.
+    ///
+    /// All other nodes are rasterized with [`draw`](Self::draw) and embedded as
+    /// PNG images, as are the cases SVG cannot express, like 3D transforms,
+    /// repeating gradients or borders with a different color on each side.
+    pub fn to_svg(&self, out: &mut impl Write) -> io::Result<()> {
+        let bounds = self.bounds();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{w}" height="{h}" viewBox="{x} {y} {w} {h}">"#,
+            x = bounds.x(),
+            y = bounds.y(),
+            w = bounds.width(),
+            h = bounds.height(),
+        )?;
+        let mut writer = SvgWriter { out, next_id: 0 };
+        writer.node(self)?;
+        writeln!(writer.out, "</svg>")
+    }
+}
+
+struct RectAttrs<'a>(&'a Rect);
+
+impl fmt::Display for RectAttrs<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            r#" x="{}" y="{}" width="{}" height="{}""#,
+            self.0.x(),
+            self.0.y(),
+            self.0.width(),
+            self.0.height()
+        )
+    }
+}
+
+// The color and opacity attributes of a paint, like `fill` and `fill-opacity`.
+struct Paint<'a>(&'static str, &'static str, &'a RGBA);
+
+impl fmt::Display for Paint<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Paint(color_attr, opacity_attr, color) = self;
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        write!(
+            f,
+            r##" {}="#{:02x}{:02x}{:02x}""##,
+            color_attr,
+            channel(color.red()),
+            channel(color.green()),
+            channel(color.blue())
+        )?;
+        if color.alpha() < 1.0 {
+            write!(f, r#" {}="{}""#, opacity_attr, color.alpha().max(0.0))?;
+        }
+        Ok(())
+    }
+}
+
+fn rounded_rect_path(rect: &RoundedRect) -> String {
+    let bounds = rect.bounds();
+    let [tl, tr, br, bl] = rect.corner();
+    let (left, top) = (bounds.x(), bounds.y());
+    let (right, bottom) = (left + bounds.width(), top + bounds.height());
+    format!(
+        "M{} {}H{}A{} {} 0 0 1 {} {}V{}A{} {} 0 0 1 {} {}H{}A{} {} 0 0 1 {} {}V{}A{} {} 0 0 1 {} {}Z",
+        left + tl.width(),
+        top,
+        right - tr.width(),
+        tr.width(),
+        tr.height(),
+        right,
+        top + tr.height(),
+        bottom - br.height(),
+        br.width(),
+        br.height(),
+        right - br.width(),
+        bottom,
+        left + bl.width(),
+        bl.width(),
+        bl.height(),
+        left,
+        bottom - bl.height(),
+        top + tl.height(),
+        tl.width(),
+        tl.height(),
+        left + tl.width(),
+        top,
+    )
+}
+
+// The color of the gradient described by `stops` at `offset`.
+fn color_at(stops: &[ColorStop], offset: f32) -> RGBA {
+    let first = match stops.first() {
+        Some(first) => first,
+        None => return RGBA::new(0.0, 0.0, 0.0, 0.0),
+    };
+    if offset <= first.offset() {
+        return first.color();
+    }
+    for pair in stops.windows(2) {
+        let (from, to) = (&pair[0], &pair[1]);
+        if offset <= to.offset() {
+            let range = to.offset() - from.offset();
+            let t = if range > 0.0 {
+                (offset - from.offset()) / range
+            } else {
+                1.0
+            };
+            let (a, b) = (from.color(), to.color());
+            let mix = |x: f32, y: f32| x + (y - x) * t;
+            return RGBA::new(
+                mix(a.red(), b.red()),
+                mix(a.green(), b.green()),
+                mix(a.blue(), b.blue()),
+                mix(a.alpha(), b.alpha()),
+            );
+        }
+    }
+    stops[stops.len() - 1].color()
+}
+
+fn io_error<E: std::error::Error + Send + Sync + 'static>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+struct SvgWriter<'a> {
+    out: &'a mut dyn Write,
+    next_id: u32,
+}
+
+impl SvgWriter<'_> {
+    fn id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}{}", prefix, self.next_id)
+    }
+
+    fn node(&mut self, node: &RenderNode) -> io::Result<()> {
+        match node.node_type() {
+            RenderNodeType::ContainerNode | RenderNodeType::DebugNode => self.children(node),
+            RenderNodeType::ColorNode => self.color(downcast(node)),
+            RenderNodeType::LinearGradientNode => self.linear_gradient(downcast(node)),
+            RenderNodeType::RadialGradientNode => self.radial_gradient(downcast(node)),
+            RenderNodeType::ConicGradientNode => self.conic_gradient(downcast(node)),
+            RenderNodeType::BorderNode => self.border(downcast(node)),
+            RenderNodeType::ClipNode => self.clip(downcast(node)),
+            RenderNodeType::RoundedClipNode => self.rounded_clip(downcast(node)),
+            RenderNodeType::TransformNode => self.transform(downcast(node)),
+            RenderNodeType::OpacityNode => self.opacity(downcast(node)),
+            RenderNodeType::ShadowNode => self.shadow(downcast(node)),
+            RenderNodeType::TextNode => self.text(downcast(node)),
+            RenderNodeType::TextureNode => self.texture(downcast(node)),
+            _ => self.raster(node, 1.0),
+        }
+    }
+
+    fn children(&mut self, node: &RenderNode) -> io::Result<()> {
+        for child in node.children() {
+            self.node(&child)?;
+        }
+        Ok(())
+    }
+
+    fn color(&mut self, node: &ColorNode) -> io::Result<()> {
+        match node.color() {
+            Some(color) => writeln!(
+                self.out,
+                "<rect{}{}/>",
+                RectAttrs(&node.bounds()),
+                Paint("fill", "fill-opacity", &color)
+            ),
+            None => Ok(()),
+        }
+    }
+
+    // Writes the stops of a gradient, mapping each offset through `map`.
+    fn stops(&mut self, stops: &[ColorStop], map: impl Fn(f32) -> f32) -> io::Result<()> {
+        for stop in stops {
+            writeln!(
+                self.out,
+                r#"<stop offset="{}"{}/>"#,
+                map(stop.offset()).clamp(0.0, 1.0),
+                Paint("stop-color", "stop-opacity", &stop.color())
+            )?;
+        }
+        Ok(())
+    }
+
+    fn linear_gradient(&mut self, node: &LinearGradientNode) -> io::Result<()> {
+        let (start, end) = (node.start(), node.end());
+        let id = self.id("gradient");
+        writeln!(
+            self.out,
+            r#"<linearGradient id="{}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}">"#,
+            id,
+            start.x(),
+            start.y(),
+            end.x(),
+            end.y()
+        )?;
+        self.stops(&node.color_stops(), |offset| offset)?;
+        writeln!(self.out, "</linearGradient>")?;
+        writeln!(
+            self.out,
+            r#"<rect{} fill="url(#{})"/>"#,
+            RectAttrs(&node.bounds()),
+            id
+        )
+    }
+
+    fn radial_gradient(&mut self, node: &RadialGradientNode) -> io::Result<()> {
+        let (start, end) = (node.start(), node.end());
+        if end <= 0.0 {
+            return self.raster(node, 1.0);
+        }
+        // The gradient is scaled so that `end` lands on the unit circle, stops
+        // are then placed between `start` and `end`.
+        let center = node.center();
+        let id = self.id("gradient");
+        writeln!(
+            self.out,
+            r#"<radialGradient id="{}" gradientUnits="userSpaceOnUse" cx="0" cy="0" r="1" gradientTransform="matrix({} 0 0 {} {} {})">"#,
+            id,
+            node.hradius() * end,
+            node.vradius() * end,
+            center.x(),
+            center.y()
+        )?;
+        self.stops(&node.color_stops(), |offset| {
+            (start + offset * (end - start)) / end
+        })?;
+        writeln!(self.out, "</radialGradient>")?;
+        writeln!(
+            self.out,
+            r#"<rect{} fill="url(#{})"/>"#,
+            RectAttrs(&node.bounds()),
+            id
+        )
+    }
+
+    fn conic_gradient(&mut self, node: &ConicGradientNode) -> io::Result<()> {
+        let bounds = node.bounds();
+        let center = node.center();
+        let stops = node.color_stops();
+        let radius = [
+            Point::new(bounds.x(), bounds.y()),
+            Point::new(bounds.x() + bounds.width(), bounds.y()),
+            Point::new(bounds.x(), bounds.y() + bounds.height()),
+            Point::new(bounds.x() + bounds.width(), bounds.y() + bounds.height()),
+        ]
+        .iter()
+        .map(|corner| (corner.x() - center.x()).hypot(corner.y() - center.y()))
+        .fold(0.0f32, f32::max)
+            + 1.0;
+
+        self.begin_clip(&format!("<rect{}/>", RectAttrs(&bounds)))?;
+        // Angles start at the top and go clockwise, like in CSS. Every wedge
+        // overlaps the next one slightly to avoid seams between them.
+        let point = |angle: f32| {
+            let angle = (node.rotation() + angle).to_radians();
+            (
+                center.x() + radius * angle.sin(),
+                center.y() - radius * angle.cos(),
+            )
+        };
+        let step = 360.0 / CONIC_WEDGES as f32;
+        for i in 0..CONIC_WEDGES {
+            let (from, to) = (i as f32 * step, (i as f32 + 1.0) * step);
+            let color = color_at(&stops, (i as f32 + 0.5) / CONIC_WEDGES as f32);
+            let (x0, y0) = point(from);
+            let (x1, y1) = point((to + step / 2.0).min(360.0));
+            writeln!(
+                self.out,
+                r#"<path d="M{} {}L{} {}L{} {}Z"{}/>"#,
+                center.x(),
+                center.y(),
+                x0,
+                y0,
+                x1,
+                y1,
+                Paint("fill", "fill-opacity", &color)
+            )?;
+        }
+        writeln!(self.out, "</g>")
+    }
+
+    fn border(&mut self, node: &BorderNode) -> io::Result<()> {
+        let colors = node.colors();
+        if colors.iter().any(|color| color != &colors[0]) {
+            return self.raster(node, 1.0);
+        }
+        let outline = node.outline();
+        let widths = node.widths();
+        let mut inner = outline.clone();
+        inner.shrink(widths[0], widths[1], widths[2], widths[3]);
+        writeln!(
+            self.out,
+            r#"<path d="{}{}" fill-rule="evenodd"{}/>"#,
+            rounded_rect_path(&outline),
+            rounded_rect_path(&inner),
+            Paint("fill", "fill-opacity", &colors[0])
+        )
+    }
+
+    // Defines a clip path with the given shape and opens a group clipped by it.
+    fn begin_clip(&mut self, shape: &str) -> io::Result<()> {
+        let id = self.id("clip");
+        writeln!(self.out, r#"<clipPath id="{}">{}</clipPath>"#, id, shape)?;
+        writeln!(self.out, r#"<g clip-path="url(#{})">"#, id)
+    }
+
+    fn clip(&mut self, node: &ClipNode) -> io::Result<()> {
+        match node.clip() {
+            Some(clip) => self.begin_clip(&format!("<rect{}/>", RectAttrs(&clip)))?,
+            None => return self.children(node),
+        }
+        self.children(node)?;
+        writeln!(self.out, "</g>")
+    }
+
+    fn rounded_clip(&mut self, node: &RoundedClipNode) -> io::Result<()> {
+        match node.clip() {
+            Some(clip) => {
+                self.begin_clip(&format!(r#"<path d="{}"/>"#, rounded_rect_path(&clip)))?
+            }
+            None => return self.children(node),
+        }
+        self.children(node)?;
+        writeln!(self.out, "</g>")
+    }
+
+    fn transform(&mut self, node: &TransformNode) -> io::Result<()> {
+        let transform = match node.transform() {
+            Some(transform) => transform,
+            None => return self.children(node),
+        };
+        match transform.category() {
+            TransformCategory::_2d
+            | TransformCategory::_2dAffine
+            | TransformCategory::_2dTranslate
+            | TransformCategory::Identity => {
+                let (xx, yx, xy, yy, dx, dy) = transform.to_2d();
+                writeln!(
+                    self.out,
+                    r#"<g transform="matrix({} {} {} {} {} {})">"#,
+                    xx, yx, xy, yy, dx, dy
+                )?;
+                self.children(node)?;
+                writeln!(self.out, "</g>")
+            }
+            _ => self.raster(node, 1.0),
+        }
+    }
+
+    fn opacity(&mut self, node: &OpacityNode) -> io::Result<()> {
+        writeln!(self.out, r#"<g opacity="{}">"#, node.opacity())?;
+        self.children(node)?;
+        writeln!(self.out, "</g>")
+    }
+
+    fn shadow(&mut self, node: &ShadowNode) -> io::Result<()> {
+        let id = self.id("shadow");
+        writeln!(
+            self.out,
+            r#"<filter id="{}" filterUnits="userSpaceOnUse"{}>"#,
+            id,
+            RectAttrs(&node.bounds())
+        )?;
+        let shadows: Vec<_> = (0..node.n_shadows())
+            .filter_map(|i| node.shadow(i))
+            .collect();
+        for (i, shadow) in shadows.iter().enumerate() {
+            writeln!(
+                self.out,
+                r#"<feGaussianBlur in="SourceAlpha" stdDeviation="{}" result="blur{i}"/>"#,
+                shadow.radius() / 2.0,
+                i = i
+            )?;
+            writeln!(
+                self.out,
+                r#"<feOffset in="blur{i}" dx="{}" dy="{}" result="offset{i}"/>"#,
+                shadow.dx(),
+                shadow.dy(),
+                i = i
+            )?;
+            writeln!(
+                self.out,
+                r#"<feFlood{} result="color{i}"/>"#,
+                Paint("flood-color", "flood-opacity", &shadow.color()),
+                i = i
+            )?;
+            writeln!(
+                self.out,
+                r#"<feComposite in="color{i}" in2="offset{i}" operator="in" result="shadow{i}"/>"#,
+                i = i
+            )?;
+        }
+        writeln!(self.out, "<feMerge>")?;
+        for i in 0..shadows.len() {
+            writeln!(self.out, r#"<feMergeNode in="shadow{}"/>"#, i)?;
+        }
+        writeln!(self.out, r#"<feMergeNode in="SourceGraphic"/>"#)?;
+        writeln!(self.out, "</feMerge>")?;
+        writeln!(self.out, "</filter>")?;
+
+        writeln!(self.out, r#"<g filter="url(#{})">"#, id)?;
+        self.children(node)?;
+        writeln!(self.out, "</g>")
+    }
+
+    fn text(&mut self, node: &TextNode) -> io::Result<()> {
+        #[cfg(any(feature = "v4_2", feature = "dox"))]
+        let color_glyphs = node.has_color_glyphs();
+        #[cfg(not(any(feature = "v4_2", feature = "dox")))]
+        let color_glyphs = false;
+
+        let scaled_font = node
+            .font()
+            .and_then(|font| font.dynamic_cast::<pangocairo::Font>().ok())
+            .and_then(|font| font.scaled_font());
+        let (scaled_font, color) = match (scaled_font, node.color()) {
+            (Some(scaled_font), Some(color)) if !color_glyphs => (scaled_font, color),
+            _ => return self.raster(node, 1.0),
+        };
+
+        let offset = node.offset().unwrap_or_else(|| Point::new(0.0, 0.0));
+        let scale = pango::SCALE as f64;
+        let mut x = offset.x() as f64;
+        let mut glyphs = Vec::new();
+        for info in node.glyphs() {
+            let geometry = info.geometry();
+            if info.glyph() < PANGO_GLYPH_EMPTY {
+                glyphs.push(cairo::Glyph {
+                    index: info.glyph() as _,
+                    x: x + geometry.x_offset() as f64 / scale,
+                    y: offset.y() as f64 + geometry.y_offset() as f64 / scale,
+                });
+            }
+            x += geometry.width() as f64 / scale;
+        }
+
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 1, 1).map_err(io_error)?;
+        let cr = cairo::Context::new(&surface).map_err(io_error)?;
+        cr.set_scaled_font(&scaled_font);
+        cr.glyph_path(&glyphs);
+        let path = cr.copy_path().map_err(io_error)?;
+
+        let mut data = String::new();
+        for segment in path.iter() {
+            match segment {
+                cairo::PathSegment::MoveTo((x, y)) => write!(data, "M{} {}", x, y),
+                cairo::PathSegment::LineTo((x, y)) => write!(data, "L{} {}", x, y),
+                cairo::PathSegment::CurveTo((x1, y1), (x2, y2), (x3, y3)) => {
+                    write!(data, "C{} {} {} {} {} {}", x1, y1, x2, y2, x3, y3)
+                }
+                cairo::PathSegment::ClosePath => write!(data, "Z"),
+            }
+            .unwrap();
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+        writeln!(
+            self.out,
+            r#"<path d="{}"{}/>"#,
+            data,
+            Paint("fill", "fill-opacity", &color)
+        )
+    }
+
+    fn texture(&mut self, node: &TextureNode) -> io::Result<()> {
+        // Embed the texture at its own resolution rather than at the node size.
+        let bounds = node.bounds();
+        let scale = node
+            .texture()
+            .map(|texture| {
+                (texture.width() as f32 / bounds.width())
+                    .max(texture.height() as f32 / bounds.height())
+            })
+            .filter(|scale| scale.is_finite())
+            .unwrap_or(1.0);
+        self.raster(node, scale.max(1.0) as f64)
+    }
+
+    // Draws the node with cairo and embeds the result as a PNG image.
+    fn raster(&mut self, node: &RenderNode, scale: f64) -> io::Result<()> {
+        let bounds = node.bounds();
+        let left = bounds.x().floor();
+        let top = bounds.y().floor();
+        let width = (bounds.x() + bounds.width()).ceil() - left;
+        let height = (bounds.y() + bounds.height()).ceil() - top;
+        if width <= 0.0 || height <= 0.0 {
+            return Ok(());
+        }
+
+        let surface = cairo::ImageSurface::create(
+            cairo::Format::ARgb32,
+            (width as f64 * scale).ceil() as i32,
+            (height as f64 * scale).ceil() as i32,
+        )
+        .map_err(io_error)?;
+        {
+            let cr = cairo::Context::new(&surface).map_err(io_error)?;
+            cr.scale(scale, scale);
+            cr.translate(-left as f64, -top as f64);
+            node.draw(&cr);
+        }
+        let mut png = Vec::new();
+        surface.write_to_png(&mut png).map_err(io_error)?;
+
+        writeln!(
+            self.out,
+            r#"<image x="{}" y="{}" width="{}" height="{}" xlink:href="data:image/png;base64,{}"/>"#,
+            left,
+            top,
+            width,
+            height,
+            glib::base64_encode(&png)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ContainerNode, Transform};
+
+    fn to_svg(node: &RenderNode) -> String {
+        let mut out = Vec::new();
+        node.to_svg(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_to_svg() {
+        let square = Rect::new(0.0, 0.0, 10.0, 10.0);
+        let red = ColorNode::new(&RGBA::RED, &square);
+        let clip = ClipNode::new(&OpacityNode::new(&red, 0.5), &Rect::new(0.0, 0.0, 5.0, 5.0));
+        let blue = ColorNode::new(&RGBA::new(0.0, 0.0, 1.0, 0.5), &square);
+        let scale = TransformNode::new(&blue, &Transform::new().scale(2.0, 2.0).unwrap());
+        let node = ContainerNode::new(&[clip.upcast(), scale.upcast()]);
+
+        assert_eq!(
+            to_svg(&node),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="20" height="20" viewBox="0 0 20 20">
+<clipPath id="clip1"><rect x="0" y="0" width="5" height="5"/></clipPath>
+<g clip-path="url(#clip1)">
+<g opacity="0.5">
+<rect x="0" y="0" width="10" height="10" fill="#ff0000"/>
+</g>
+</g>
+<g transform="matrix(2 0 0 2 0 0)">
+<rect x="0" y="0" width="10" height="10" fill="#0000ff" fill-opacity="0.5"/>
+</g>
+</svg>
+"##
+        );
+    }
+
+    #[test]
+    fn test_to_svg_raster() {
+        let outline = RoundedRect::from_rect(Rect::new(0.0, 0.0, 10.0, 10.0), 0.0);
+        let border = BorderNode::new(
+            &outline,
+            &[1.0; 4],
+            &[RGBA::RED, RGBA::GREEN, RGBA::RED, RGBA::GREEN],
+        );
+        let svg = to_svg(&border);
+        assert!(svg.contains(
+            r#"<image x="0" y="0" width="10" height="10" xlink:href="data:image/png;base64,"#
+        ));
+
+        let border = BorderNode::new(&outline, &[1.0; 4], &[RGBA::RED; 4]);
+        let svg = to_svg(&border);
+        assert!(svg.contains(r##"fill-rule="evenodd" fill="#ff0000"/>"##));
+        assert!(!svg.contains("<image"));
+    }
+
+    #[test]
+    fn test_color_at() {
+        let stops = [
+            ColorStop::new(0.0, RGBA::new(0.0, 0.0, 0.0, 1.0)),
+            ColorStop::new(0.5, RGBA::new(1.0, 0.0, 0.0, 1.0)),
+            ColorStop::new(1.0, RGBA::new(1.0, 1.0, 0.0, 0.0)),
+        ];
+        assert_eq!(color_at(&stops, -1.0), stops[0].color());
+        assert_eq!(color_at(&stops, 0.25), RGBA::new(0.5, 0.0, 0.0, 1.0));
+        assert_eq!(color_at(&stops, 0.75), RGBA::new(1.0, 0.5, 0.0, 0.5));
+        assert_eq!(color_at(&stops, 2.0), stops[2].color());
+        assert_eq!(color_at(&[], 0.5), RGBA::new(0.0, 0.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,193 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::{
+    BlendNode, BlurNode, BorderNode, CairoNode, ClipNode, ColorMatrixNode, ColorNode,
+    ConicGradientNode, ContainerNode, CrossFadeNode, DebugNode, GLShaderNode, InsetShadowNode,
+    LinearGradientNode, OpacityNode, OutsetShadowNode, RadialGradientNode, RenderNode,
+    RenderNodeType, RepeatNode, RepeatingLinearGradientNode, RepeatingRadialGradientNode,
+    RoundedClipNode, ShadowNode, TextNode, TextureNode, TransformNode,
+};
+
+macro_rules! define_visitor {
+    ($($method:ident => $rust_type:ident,)+) => {
+        // rustdoc-stripper-ignore-next
+        /// A visitor over a tree of [`RenderNode`](crate::RenderNode)s, with one
+        /// method per [`RenderNodeType`](crate::RenderNodeType).
+        ///
+        /// The default implementation of every method visits the children of the
+        /// node, so implementors only have to override the methods for the node
+        /// types they are interested in. An overriding method has to call
+        /// [`visit_children`](Self::visit_children) itself to keep descending.
+        ///
+        /// ```
+        /// use gsk4 as gsk;
+        /// use gsk::prelude::*;
+        /// use gsk::RenderNodeVisitor;
+        ///
+        /// #[derive(Default)]
+        /// struct ColorCounter(usize);
+        ///
+        /// impl RenderNodeVisitor for ColorCounter {
+        ///     fn visit_color_node(&mut self, _node: &gsk::ColorNode) {
+        ///         self.0 += 1;
+        ///     }
+        /// }
+        ///
+        /// let rect = gsk::graphene::Rect::new(0.0, 0.0, 10.0, 10.0);
+        /// let color = gsk::ColorNode::new(&gsk::gdk::RGBA::BLACK, &rect).upcast();
+        /// let node = gsk::ContainerNode::new(&[color.clone(), color]);
+        ///
+        /// let mut counter = ColorCounter::default();
+        /// node.accept(&mut counter);
+        /// assert_eq!(counter.0, 2);
+        /// ```
+        pub trait RenderNodeVisitor {
+            $(
+                fn $method(&mut self, node: &$rust_type) {
+                    self.visit_children(node);
+                }
+            )+
+
+            // rustdoc-stripper-ignore-next
+            /// Called for nodes of a type unknown to these bindings.
+            fn visit_unknown_node(&mut self, node: &RenderNode) {
+                self.visit_children(node);
+            }
+
+            // rustdoc-stripper-ignore-next
+            /// Visits the direct children of `node`, in paint order.
+            fn visit_children(&mut self, node: &RenderNode) {
+                for child in node.children() {
+                    child.accept(self);
+                }
+            }
+        }
+
+        impl RenderNode {
+            // rustdoc-stripper-ignore-next
+            /// Calls the method of `visitor` matching the type of this node.
+            pub fn accept<V: RenderNodeVisitor + ?Sized>(&self, visitor: &mut V) {
+                $(
+                    if let Some(node) = self.downcast_ref::<$rust_type>() {
+                        return visitor.$method(node);
+                    }
+                )+
+                visitor.visit_unknown_node(self)
+            }
+        }
+    };
+}
+
+define_visitor! {
+    visit_container_node => ContainerNode,
+    visit_cairo_node => CairoNode,
+    visit_color_node => ColorNode,
+    visit_linear_gradient_node => LinearGradientNode,
+    visit_repeating_linear_gradient_node => RepeatingLinearGradientNode,
+    visit_radial_gradient_node => RadialGradientNode,
+    visit_repeating_radial_gradient_node => RepeatingRadialGradientNode,
+    visit_conic_gradient_node => ConicGradientNode,
+    visit_border_node => BorderNode,
+    visit_texture_node => TextureNode,
+    visit_inset_shadow_node => InsetShadowNode,
+    visit_outset_shadow_node => OutsetShadowNode,
+    visit_transform_node => TransformNode,
+    visit_opacity_node => OpacityNode,
+    visit_color_matrix_node => ColorMatrixNode,
+    visit_repeat_node => RepeatNode,
+    visit_clip_node => ClipNode,
+    visit_rounded_clip_node => RoundedClipNode,
+    visit_shadow_node => ShadowNode,
+    visit_blend_node => BlendNode,
+    visit_cross_fade_node => CrossFadeNode,
+    visit_text_node => TextNode,
+    visit_blur_node => BlurNode,
+    visit_debug_node => DebugNode,
+    visit_gl_shader_node => GLShaderNode,
+}
+
+impl RenderNode {
+    // rustdoc-stripper-ignore-next
+    /// Returns the direct children of this node, in paint order.
+    ///
+    /// This works across all the node types that wrap other nodes; for
+    /// [`BlendNode`] the bottom child comes first, for [`CrossFadeNode`] the
+    /// start child comes first.
+    pub fn children(&self) -> Vec<RenderNode> {
+        match self.node_type() {
+            RenderNodeType::ContainerNode => {
+                let node = self.downcast_ref::<ContainerNode>().unwrap();
+                (0..node.n_children())
+                    .filter_map(|i| node.child(i))
+                    .collect()
+            }
+            RenderNodeType::GlShaderNode => {
+                let node = self.downcast_ref::<GLShaderNode>().unwrap();
+                (0..node.n_children())
+                    .filter_map(|i| node.child(i))
+                    .collect()
+            }
+            RenderNodeType::BlendNode => {
+                let node = self.downcast_ref::<BlendNode>().unwrap();
+                node.bottom_child()
+                    .into_iter()
+                    .chain(node.top_child())
+                    .collect()
+            }
+            RenderNodeType::CrossFadeNode => {
+                let node = self.downcast_ref::<CrossFadeNode>().unwrap();
+                node.start_child()
+                    .into_iter()
+                    .chain(node.end_child())
+                    .collect()
+            }
+            RenderNodeType::ShadowNode => {
+                vec![self.downcast_ref::<ShadowNode>().unwrap().child()]
+            }
+            _ => self.single_child().into_iter().collect(),
+        }
+    }
+
+    // The single child of the node types that wrap exactly one node.
+    fn single_child(&self) -> Option<RenderNode> {
+        match self.node_type() {
+            RenderNodeType::TransformNode => self.downcast_ref::<TransformNode>()?.child(),
+            RenderNodeType::OpacityNode => self.downcast_ref::<OpacityNode>()?.child(),
+            RenderNodeType::ColorMatrixNode => self.downcast_ref::<ColorMatrixNode>()?.child(),
+            RenderNodeType::RepeatNode => self.downcast_ref::<RepeatNode>()?.child(),
+            RenderNodeType::ClipNode => self.downcast_ref::<ClipNode>()?.child(),
+            RenderNodeType::RoundedClipNode => self.downcast_ref::<RoundedClipNode>()?.child(),
+            RenderNodeType::BlurNode => self.downcast_ref::<BlurNode>()?.child(),
+            RenderNodeType::DebugNode => self.downcast_ref::<DebugNode>()?.child(),
+            _ => None,
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Returns a depth-first, pre-order iterator over all the descendants of this
+    /// node, not including the node itself.
+    pub fn descendants(&self) -> Descendants {
+        let mut stack = self.children();
+        stack.reverse();
+        Descendants { stack }
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Iterator returned by [`RenderNode::descendants`](crate::RenderNode::descendants).
+#[derive(Debug, Clone)]
+pub struct Descendants {
+    stack: Vec<RenderNode>,
+}
+
+impl Iterator for Descendants {
+    type Item = RenderNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children().into_iter().rev());
+        Some(node)
+    }
+}
+
+impl std::iter::FusedIterator for Descendants {}
@@ -1,7 +1,10 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+use crate::{Transform, TransformCategory};
 use glib::translate::*;
 use graphene::{Point, Rect, Size};
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::fmt;
 use std::mem;
 
 glib::wrapper! {
@@ -76,6 +79,24 @@ impl RoundedRect {
         }
     }
 
+    pub fn bounds(&self) -> Rect {
+        unsafe { from_glib_none(&self.0.bounds as *const _) }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The size of the corners, in the order top left, top right, bottom right,
+    /// bottom left.
+    pub fn corner(&self) -> [Size; 4] {
+        unsafe {
+            [
+                from_glib_none(&self.0.corner[0] as *const _),
+                from_glib_none(&self.0.corner[1] as *const _),
+                from_glib_none(&self.0.corner[2] as *const _),
+                from_glib_none(&self.0.corner[3] as *const _),
+            ]
+        }
+    }
+
     #[doc(alias = "gsk_rounded_rect_normalize")]
     pub fn normalize(&mut self) {
         unsafe {
@@ -131,4 +152,302 @@ impl RoundedRect {
             ))
         }
     }
+
+    // rustdoc-stripper-ignore-next
+    /// The smallest rectangle containing the bounds of both rounded rectangles.
+    pub fn union_bounds(&self, other: &RoundedRect) -> Rect {
+        self.bounds().union(&other.bounds())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The intersection of the bounds of both rounded rectangles, if they
+    /// intersect.
+    pub fn intersection_bounds(&self, other: &RoundedRect) -> Option<Rect> {
+        self.bounds().intersection(&other.bounds())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Moves each side outwards by the given amount, the opposite of
+    /// [`shrink`](Self::shrink).
+    ///
+    /// Rounded corners grow along with the sides, like CSS spread shadows, while
+    /// square corners stay square. Negative amounts move the sides inwards, and
+    /// the size and the corners never go below zero.
+    pub fn grow(&mut self, top: f32, right: f32, bottom: f32, left: f32) {
+        let bounds = self.bounds();
+        let width = bounds.width() + left + right;
+        let height = bounds.height() + top + bottom;
+        let x = if width < 0.0 {
+            bounds.x() - left + width / 2.0
+        } else {
+            bounds.x() - left
+        };
+        let y = if height < 0.0 {
+            bounds.y() - top + height / 2.0
+        } else {
+            bounds.y() - top
+        };
+        let bounds = Rect::new(x, y, width.max(0.0), height.max(0.0));
+
+        let grow_corner = |corner: &Size, dx: f32, dy: f32| {
+            if corner.width() <= 0.0 || corner.height() <= 0.0 {
+                Size::new(0.0, 0.0)
+            } else {
+                Size::new(
+                    (corner.width() + dx).max(0.0),
+                    (corner.height() + dy).max(0.0),
+                )
+            }
+        };
+        let [tl, tr, br, bl] = self.corner();
+        self.init(
+            bounds,
+            grow_corner(&tl, left, top),
+            grow_corner(&tr, right, top),
+            grow_corner(&br, right, bottom),
+            grow_corner(&bl, left, bottom),
+        );
+        self.normalize();
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Linearly interpolates the bounds and the corners of `from` and `to`.
+    pub fn interpolate(from: &RoundedRect, to: &RoundedRect, progress: f32) -> RoundedRect {
+        skip_assert_initialized!();
+        let mix = |a: f32, b: f32| a + (b - a) * progress;
+        let (a, b) = (from.bounds(), to.bounds());
+        let bounds = Rect::new(
+            mix(a.x(), b.x()),
+            mix(a.y(), b.y()),
+            mix(a.width(), b.width()).max(0.0),
+            mix(a.height(), b.height()).max(0.0),
+        );
+        let (a, b) = (from.corner(), to.corner());
+        let corner = |i: usize| {
+            Size::new(
+                mix(a[i].width(), b[i].width()).max(0.0),
+                mix(a[i].height(), b[i].height()).max(0.0),
+            )
+        };
+        RoundedRect::new(bounds, corner(0), corner(1), corner(2), corner(3))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Scales the bounds and the corners, relative to the origin.
+    ///
+    /// Negative factors mirror the rectangle, swapping its corners.
+    pub fn scale(&mut self, factor_x: f32, factor_y: f32) {
+        let bounds = self.bounds();
+        let (x1, x2) = (
+            bounds.x() * factor_x,
+            (bounds.x() + bounds.width()) * factor_x,
+        );
+        let (y1, y2) = (
+            bounds.y() * factor_y,
+            (bounds.y() + bounds.height()) * factor_y,
+        );
+        let bounds = Rect::new(x1.min(x2), y1.min(y2), (x2 - x1).abs(), (y2 - y1).abs());
+
+        let scale = |corner: &Size| {
+            Size::new(
+                corner.width() * factor_x.abs(),
+                corner.height() * factor_y.abs(),
+            )
+        };
+        let mut corners = self.corner();
+        if factor_x < 0.0 {
+            corners.swap(0, 1);
+            corners.swap(2, 3);
+        }
+        if factor_y < 0.0 {
+            corners.swap(0, 3);
+            corners.swap(1, 2);
+        }
+        let [tl, tr, br, bl] = corners;
+        self.init(bounds, scale(&tl), scale(&tr), scale(&br), scale(&bl));
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Applies `transform` to the rounded rectangle.
+    ///
+    /// Returns `None` if the transform is more than a scale and a translation,
+    /// as the result would no longer be a rounded rectangle.
+    pub fn transform(&self, transform: &Transform) -> Option<RoundedRect> {
+        match transform.category() {
+            TransformCategory::Identity
+            | TransformCategory::_2dTranslate
+            | TransformCategory::_2dAffine => {
+                let (scale_x, scale_y, dx, dy) = transform.to_affine();
+                let mut rect = self.clone();
+                rect.scale(scale_x, scale_y);
+                rect.offset(dx, dy);
+                Some(rect)
+            }
+            _ => None,
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Adds the outline of the rounded rectangle to the current path of `cr`,
+    /// as a new sub-path.
+    pub fn add_to_cairo_path(&self, cr: &cairo::Context) {
+        let bounds = self.bounds();
+        let (left, top) = (bounds.x() as f64, bounds.y() as f64);
+        let (right, bottom) = (left + bounds.width() as f64, top + bounds.height() as f64);
+        let [tl, tr, br, bl] = self.corner();
+
+        // Draws the corner at (x, y), where the signs give the direction of the
+        // center of its arc, starting at `angle` over a quarter turn.
+        let corner = |corner: &Size, x: f64, y: f64, sign_x: f64, sign_y: f64, angle: f64| {
+            let (rx, ry) = (corner.width() as f64, corner.height() as f64);
+            if rx <= 0.0 || ry <= 0.0 {
+                cr.line_to(x, y);
+                return;
+            }
+            cr.save().ok();
+            cr.translate(x + sign_x * rx, y + sign_y * ry);
+            cr.scale(rx, ry);
+            cr.arc(0.0, 0.0, 1.0, angle, angle + FRAC_PI_2);
+            cr.restore().ok();
+        };
+
+        cr.new_sub_path();
+        corner(&tl, left, top, 1.0, 1.0, PI);
+        corner(&tr, right, top, -1.0, 1.0, -FRAC_PI_2);
+        corner(&br, right, bottom, -1.0, -1.0, 0.0);
+        corner(&bl, left, bottom, 1.0, -1.0, FRAC_PI_2);
+        cr.close_path();
+    }
+}
+
+impl PartialEq for RoundedRect {
+    fn eq(&self, other: &Self) -> bool {
+        self.bounds() == other.bounds() && self.corner() == other.corner()
+    }
+}
+
+impl fmt::Debug for RoundedRect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RoundedRect")
+            .field("bounds", &self.bounds())
+            .field("corner", &self.corner())
+            .finish()
+    }
+}
+
+// The serialized form of a `RoundedRect`, as plain numbers.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "RoundedRect")]
+struct RoundedRectData {
+    bounds: [f32; 4],
+    corners: [[f32; 2]; 4],
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RoundedRect {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bounds = self.bounds();
+        let corners = self.corner();
+        RoundedRectData {
+            bounds: [bounds.x(), bounds.y(), bounds.width(), bounds.height()],
+            corners: [0, 1, 2, 3].map(|i| [corners[i].width(), corners[i].height()]),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RoundedRect {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RoundedRectData { bounds, corners } = RoundedRectData::deserialize(deserializer)?;
+        let [x, y, width, height] = bounds;
+        let [tl, tr, br, bl] = corners.map(|[width, height]| Size::new(width, height));
+        Ok(RoundedRect::new(
+            Rect::new(x, y, width, height),
+            tl,
+            tr,
+            br,
+            bl,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grow() {
+        let mut rect = RoundedRect::from_rect(Rect::new(10.0, 10.0, 20.0, 20.0), 5.0);
+        rect.grow(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(rect.bounds(), Rect::new(6.0, 9.0, 26.0, 24.0));
+        assert_eq!(
+            rect.corner(),
+            [
+                Size::new(9.0, 6.0),
+                Size::new(7.0, 6.0),
+                Size::new(7.0, 8.0),
+                Size::new(9.0, 8.0),
+            ]
+        );
+
+        let mut rect = RoundedRect::from_rect(Rect::new(0.0, 0.0, 10.0, 10.0), 0.0);
+        rect.grow(2.0, 2.0, 2.0, 2.0);
+        assert!(rect.is_rectilinear());
+        rect.grow(-10.0, -10.0, -10.0, -10.0);
+        assert_eq!(rect.bounds(), Rect::new(5.0, 5.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let from = RoundedRect::from_rect(Rect::new(0.0, 0.0, 10.0, 10.0), 0.0);
+        let to = RoundedRect::from_rect(Rect::new(10.0, 20.0, 30.0, 40.0), 4.0);
+        assert_eq!(RoundedRect::interpolate(&from, &to, 0.0), from);
+        assert_eq!(RoundedRect::interpolate(&from, &to, 1.0), to);
+        assert_eq!(
+            RoundedRect::interpolate(&from, &to, 0.5),
+            RoundedRect::from_rect(Rect::new(5.0, 10.0, 20.0, 25.0), 2.0)
+        );
+    }
+
+    #[test]
+    fn test_scale() {
+        let mut rect = RoundedRect::new(
+            Rect::new(1.0, 2.0, 3.0, 4.0),
+            Size::new(1.0, 1.0),
+            Size::new(2.0, 2.0),
+            Size::new(0.0, 0.0),
+            Size::new(0.0, 0.0),
+        );
+        rect.scale(2.0, -1.0);
+        assert_eq!(rect.bounds(), Rect::new(2.0, -6.0, 6.0, 4.0));
+        assert_eq!(
+            rect.corner(),
+            [
+                Size::new(0.0, 0.0),
+                Size::new(0.0, 0.0),
+                Size::new(4.0, 2.0),
+                Size::new(2.0, 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transform() {
+        let rect = RoundedRect::from_rect(Rect::new(0.0, 0.0, 10.0, 10.0), 2.0);
+        let transform = Transform::new()
+            .translate(&Point::new(5.0, 5.0))
+            .unwrap()
+            .scale(2.0, 2.0)
+            .unwrap();
+        assert_eq!(
+            rect.transform(&transform),
+            Some(RoundedRect::from_rect(Rect::new(5.0, 5.0, 20.0, 20.0), 4.0))
+        );
+        assert_eq!(rect.transform(&Transform::new()), Some(rect.clone()));
+
+        let rotation = Transform::new().rotate(45.0).unwrap();
+        assert_eq!(rect.transform(&rotation), None);
+    }
 }
@@ -0,0 +1,220 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::{GLShader, GLUniformType, ShaderArgsBuilder};
+use std::fmt;
+use std::marker::PhantomData;
+
+// rustdoc-stripper-ignore-next
+/// A Rust type that can be passed as a single uniform of a [`GLShader`](crate::GLShader).
+pub trait ShaderArg {
+    const UNIFORM_TYPE: GLUniformType;
+
+    fn set_arg(&self, builder: &ShaderArgsBuilder, idx: i32);
+}
+
+impl ShaderArg for f32 {
+    const UNIFORM_TYPE: GLUniformType = GLUniformType::Float;
+
+    fn set_arg(&self, builder: &ShaderArgsBuilder, idx: i32) {
+        builder.set_float(idx, *self);
+    }
+}
+
+impl ShaderArg for i32 {
+    const UNIFORM_TYPE: GLUniformType = GLUniformType::Int;
+
+    fn set_arg(&self, builder: &ShaderArgsBuilder, idx: i32) {
+        builder.set_int(idx, *self);
+    }
+}
+
+impl ShaderArg for u32 {
+    const UNIFORM_TYPE: GLUniformType = GLUniformType::Uint;
+
+    fn set_arg(&self, builder: &ShaderArgsBuilder, idx: i32) {
+        builder.set_uint(idx, *self);
+    }
+}
+
+impl ShaderArg for bool {
+    const UNIFORM_TYPE: GLUniformType = GLUniformType::Bool;
+
+    fn set_arg(&self, builder: &ShaderArgsBuilder, idx: i32) {
+        builder.set_bool(idx, *self);
+    }
+}
+
+impl ShaderArg for graphene::Vec2 {
+    const UNIFORM_TYPE: GLUniformType = GLUniformType::Vec2;
+
+    fn set_arg(&self, builder: &ShaderArgsBuilder, idx: i32) {
+        builder.set_vec2(idx, self);
+    }
+}
+
+impl ShaderArg for graphene::Vec3 {
+    const UNIFORM_TYPE: GLUniformType = GLUniformType::Vec3;
+
+    fn set_arg(&self, builder: &ShaderArgsBuilder, idx: i32) {
+        builder.set_vec3(idx, self);
+    }
+}
+
+impl ShaderArg for graphene::Vec4 {
+    const UNIFORM_TYPE: GLUniformType = GLUniformType::Vec4;
+
+    fn set_arg(&self, builder: &ShaderArgsBuilder, idx: i32) {
+        builder.set_vec4(idx, self);
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// A struct whose fields map to the uniforms of a [`GLShader`](crate::GLShader).
+///
+/// This is usually implemented with `#[derive(gtk::ShaderArgs)]`, which maps
+/// every field to the uniform of the same name. The uniform name can be
+/// overridden with `#[uniform(name = "...")]`.
+pub trait ShaderArgs {
+    // rustdoc-stripper-ignore-next
+    /// The name and type of the uniform matching each field, in field order.
+    const UNIFORMS: &'static [(&'static str, GLUniformType)];
+
+    // rustdoc-stripper-ignore-next
+    /// Sets each field on `builder`, where `indices` holds the uniform index
+    /// of each entry of [`UNIFORMS`](Self::UNIFORMS).
+    fn set_args(&self, builder: &ShaderArgsBuilder, indices: &[i32]);
+}
+
+// rustdoc-stripper-ignore-next
+/// A [`GLShader`](crate::GLShader) whose uniforms have been checked against the
+/// fields of `T`.
+///
+/// With gtk4, `#[derive(gtk::ShaderArgs)]` writes the [`ShaderArgs`]
+/// implementation below, given `#[uniform(name = "ripple_center")]` on `center`.
+///
+/// ```no_run
+/// use gsk4 as gsk;
+/// use gsk::{graphene, GLUniformType, ShaderArg, ShaderArgsBuilder};
+///
+/// struct Ripple {
+///     time: f32,
+///     center: graphene::Vec2,
+/// }
+///
+/// impl gsk::ShaderArgs for Ripple {
+///     const UNIFORMS: &'static [(&'static str, GLUniformType)] = &[
+///         ("time", GLUniformType::Float),
+///         ("ripple_center", GLUniformType::Vec2),
+///     ];
+///
+///     fn set_args(&self, builder: &ShaderArgsBuilder, indices: &[i32]) {
+///         self.time.set_arg(builder, indices[0]);
+///         self.center.set_arg(builder, indices[1]);
+///     }
+/// }
+///
+/// let shader = gsk::GLShader::from_resource("/org/example/ripple.glsl");
+/// let shader = gsk::TypedGLShader::<Ripple>::new(shader).expect("Shader and struct disagree");
+/// let args = shader
+///     .args(&Ripple {
+///         time: 0.5,
+///         center: graphene::Vec2::new(0.5, 0.5),
+///     })
+///     .unwrap();
+/// ```
+pub struct TypedGLShader<T> {
+    shader: GLShader,
+    indices: Vec<i32>,
+    phantom: PhantomData<fn(&T)>,
+}
+
+impl<T: ShaderArgs> TypedGLShader<T> {
+    // rustdoc-stripper-ignore-next
+    /// Checks that every field of `T` matches a uniform of `shader` by name and
+    /// type, and that every uniform of `shader` is covered by a field.
+    pub fn new(shader: GLShader) -> Result<Self, glib::BoolError> {
+        let mut indices = Vec::with_capacity(T::UNIFORMS.len());
+        for &(name, uniform_type) in T::UNIFORMS {
+            let idx = shader.find_uniform_by_name(name);
+            if idx < 0 {
+                return Err(glib::bool_error!(
+                    "The shader has no uniform named `{}`",
+                    name
+                ));
+            }
+            let actual = shader.uniform_type(idx);
+            if actual != uniform_type {
+                return Err(glib::bool_error!(
+                    "The uniform `{}` has type {}, but the field has type {}",
+                    name,
+                    actual,
+                    uniform_type
+                ));
+            }
+            indices.push(idx);
+        }
+
+        if let Some(missing) = (0..shader.n_uniforms()).find(|idx| !indices.contains(idx)) {
+            return Err(glib::bool_error!(
+                "The uniform `{}` is not set by any field",
+                shader.uniform_name(missing).as_deref().unwrap_or("")
+            ));
+        }
+
+        Ok(Self {
+            shader,
+            indices,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn shader(&self) -> &GLShader {
+        &self.shader
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Builds the uniform data of the shader from `args`.
+    ///
+    /// Fails if the uniforms of `T` changed since the shader was checked,
+    /// which can only happen with a hand-written [`ShaderArgs`] implementation,
+    /// or if GSK can't build the data.
+    pub fn args(&self, args: &T) -> Result<glib::Bytes, glib::BoolError> {
+        if T::UNIFORMS.len() != self.indices.len() {
+            return Err(glib::bool_error!(
+                "{} uniforms were checked, but the arguments have {}",
+                self.indices.len(),
+                T::UNIFORMS.len()
+            ));
+        }
+        let builder = ShaderArgsBuilder::new(&self.shader, None);
+        args.set_args(&builder, &self.indices);
+        builder
+            .to_args()
+            .ok_or_else(|| glib::bool_error!("Failed to build the shader arguments"))
+    }
+}
+
+impl<T> Clone for TypedGLShader<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shader: self.shader.clone(),
+            indices: self.indices.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for TypedGLShader<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TypedGLShader")
+            .field("shader", &self.shader)
+            .field("indices", &self.indices)
+            .finish()
+    }
+}
+
+impl<T> AsRef<GLShader> for TypedGLShader<T> {
+    fn as_ref(&self) -> &GLShader {
+        &self.shader
+    }
+}
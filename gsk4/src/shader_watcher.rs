@@ -0,0 +1,398 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use crate::{GLShader, Renderer};
+use gdk::gio;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+
+// rustdoc-stripper-ignore-next
+/// An error loading or compiling the shader of a [`ShaderWatcher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderError {
+    path: PathBuf,
+    message: String,
+    line: Option<usize>,
+    source_line: Option<String>,
+}
+
+impl ShaderError {
+    fn new(path: &Path, message: String, source: Option<&str>) -> Self {
+        let line = error_line(&message);
+        let source_line = line
+            .zip(source)
+            .and_then(|(line, source)| source.lines().nth(line.checked_sub(1)?))
+            .map(str::to_owned);
+        Self {
+            path: path.to_owned(),
+            message,
+            line,
+            source_line,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The message of the GL driver or of the file system.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The line of the shader file the error points to, starting at 1, if the
+    /// driver reported one.
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The content of [`line`](Self::line) in the shader file.
+    pub fn source_line(&self) -> Option<&str> {
+        self.source_line.as_deref()
+    }
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        write!(f, ": {}", self.message.trim_end())?;
+        if let Some(source_line) = &self.source_line {
+            write!(f, "\n    {}", source_line.trim())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+// Finds the line number in GLSL compiler messages, which depending on the
+// driver look like `0:12(5): error`, `ERROR: 0:12: ...` or `0(12) : error`.
+fn error_line(message: &str) -> Option<usize> {
+    message.match_indices('0').find_map(|(idx, _)| {
+        let rest = &message[idx + 1..];
+        if idx > 0 && message.as_bytes()[idx - 1].is_ascii_digit() {
+            return None;
+        }
+        let (digits, terminators): (&str, &[char]) = if let Some(rest) = rest.strip_prefix(':') {
+            (rest, &[':', '('])
+        } else if let Some(rest) = rest.strip_prefix('(') {
+            (rest, &[')'])
+        } else {
+            return None;
+        };
+        let end = digits.find(|c: char| !c.is_ascii_digit())?;
+        if end == 0 || !terminators.contains(&digits[end..].chars().next()?) {
+            return None;
+        }
+        digits[..end].parse().ok()
+    })
+}
+
+type ChangedCallback = Rc<dyn Fn(Result<&GLShader, &ShaderError>)>;
+
+struct Inner {
+    path: PathBuf,
+    renderer: Option<Renderer>,
+    shader: RefCell<GLShader>,
+    error: RefCell<Option<ShaderError>>,
+    // The content of the file at the last reload, to skip the reloads of
+    // file events that did not change it.
+    data: RefCell<Option<Vec<u8>>>,
+    callbacks: RefCell<Vec<(u64, ChangedCallback)>>,
+    next_callback_id: Cell<u64>,
+    monitor: RefCell<Option<gio::FileMonitor>>,
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, ShaderError> {
+    std::fs::read(path).map_err(|err| ShaderError::new(path, err.to_string(), None))
+}
+
+fn load(path: &Path, data: &[u8], renderer: Option<&Renderer>) -> Result<GLShader, ShaderError> {
+    let shader = GLShader::from_bytes(&glib::Bytes::from(data));
+    if let Some(renderer) = renderer {
+        shader.compile(renderer).map_err(|err| {
+            let source = String::from_utf8_lossy(data);
+            ShaderError::new(path, err.to_string(), Some(&source))
+        })?;
+    }
+    Ok(shader)
+}
+
+impl Inner {
+    // Reloads the shader, unless the file is unchanged since the last reload
+    // and `force` is false.
+    fn reload(&self, force: bool) {
+        let result = match read(&self.path) {
+            Ok(data) => {
+                if !force && self.data.borrow().as_ref() == Some(&data) {
+                    return;
+                }
+                let result = load(&self.path, &data, self.renderer.as_ref());
+                self.data.replace(Some(data));
+                result
+            }
+            Err(err) => {
+                self.data.replace(None);
+                Err(err)
+            }
+        };
+        match &result {
+            Ok(shader) => {
+                self.shader.replace(shader.clone());
+                self.error.replace(None);
+            }
+            Err(err) => {
+                glib::g_warning!("ShaderWatcher", "{}", err);
+                self.error.replace(Some(err.clone()));
+            }
+        }
+        // Clone the shader and the callbacks so that the callbacks can query
+        // the watcher and remove themselves.
+        let shader = self.shader.borrow().clone();
+        let callbacks: Vec<ChangedCallback> = self
+            .callbacks
+            .borrow()
+            .iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in callbacks {
+            callback(result.as_ref().map(|_| &shader));
+        }
+    }
+
+    // Editors and file systems report a change with one or more events, like
+    // `Created` followed by `ChangesDoneHint`, so only changes of the content
+    // reload the shader.
+    fn file_changed(&self, event: gio::FileMonitorEvent) {
+        if matches!(
+            event,
+            gio::FileMonitorEvent::ChangesDoneHint | gio::FileMonitorEvent::Created
+        ) {
+            self.reload(false);
+        }
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// The handle of a callback connected with [`ShaderWatcher::connect_changed`].
+#[derive(Debug)]
+pub struct ShaderChangedCallbackId {
+    id: u64,
+    inner: Weak<Inner>,
+}
+
+impl PartialEq for ShaderChangedCallbackId {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && Weak::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl ShaderChangedCallbackId {
+    // rustdoc-stripper-ignore-next
+    /// Disconnects the callback from its watcher.
+    pub fn remove(self) {
+        if let Some(inner) = self.inner.upgrade() {
+            inner
+                .callbacks
+                .borrow_mut()
+                .retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Loads a [`GLShader`](crate::GLShader) from a file and reloads it whenever the
+/// file changes, for use during development.
+///
+/// When a renderer is given, every version of the shader is compiled with
+/// [`GLShader::compile`](crate::GLShader::compile) before it replaces the
+/// previous one, so a shader that fails to compile keeps the last working
+/// version on screen and reports a [`ShaderError`] pointing at the offending
+/// line instead.
+///
+/// Widgets using the shader have to be redrawn after a reload, which GTK's
+/// `ShaderWatcherWidgetExt::queue_draw_on_reload` does.
+///
+/// ```no_run
+/// use gsk4 as gsk;
+///
+/// # fn example(renderer: &gsk::Renderer) {
+/// let watcher = gsk::ShaderWatcher::new("effects/ripple.glsl", Some(renderer)).unwrap();
+/// watcher.connect_changed(|result| match result {
+///     Ok(_) => println!("Reloaded the ripple shader"),
+///     Err(err) => eprintln!("{}", err),
+/// });
+/// # }
+/// ```
+pub struct ShaderWatcher {
+    inner: Rc<Inner>,
+}
+
+impl ShaderWatcher {
+    // rustdoc-stripper-ignore-next
+    /// Loads the shader at `path` and starts watching the file.
+    ///
+    /// Fails if the shader can't be loaded or compiled the first time.
+    pub fn new<P: AsRef<Path>>(path: P, renderer: Option<&Renderer>) -> Result<Self, ShaderError> {
+        assert_initialized_main_thread!();
+        let path = path.as_ref().to_owned();
+        let renderer = renderer.cloned();
+        let data = read(&path)?;
+        let shader = load(&path, &data, renderer.as_ref())?;
+        let inner = Rc::new(Inner {
+            path,
+            renderer,
+            shader: RefCell::new(shader),
+            error: RefCell::new(None),
+            data: RefCell::new(Some(data)),
+            callbacks: RefCell::new(Vec::new()),
+            next_callback_id: Cell::new(0),
+            monitor: RefCell::new(None),
+        });
+
+        let monitor = gio::File::for_path(&inner.path)
+            .monitor_file(gio::FileMonitorFlags::NONE, None::<&gio::Cancellable>)
+            .map_err(|err| ShaderError::new(&inner.path, err.to_string(), None))?;
+        let weak: Weak<Inner> = Rc::downgrade(&inner);
+        monitor.connect_changed(move |_, _, _, event| {
+            if let Some(inner) = weak.upgrade() {
+                inner.file_changed(event);
+            }
+        });
+        inner.monitor.replace(Some(monitor));
+
+        Ok(Self { inner })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The last version of the shader that loaded successfully.
+    pub fn shader(&self) -> GLShader {
+        self.inner.shader.borrow().clone()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The error of the last reload, if it failed.
+    pub fn error(&self) -> Option<ShaderError> {
+        self.inner.error.borrow().clone()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Reloads the shader now, without waiting for the file to change.
+    pub fn reload(&self) {
+        self.inner.reload(true);
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Calls `f` after every reload, with the new shader or the reason it was
+    /// rejected. This is the place to queue a redraw of the widgets using the
+    /// shader.
+    ///
+    /// The callback stays connected until the returned id is removed, or the
+    /// watcher is dropped.
+    pub fn connect_changed<F: Fn(Result<&GLShader, &ShaderError>) + 'static>(
+        &self,
+        f: F,
+    ) -> ShaderChangedCallbackId {
+        let id = self.inner.next_callback_id.get();
+        self.inner.next_callback_id.set(id + 1);
+        self.inner.callbacks.borrow_mut().push((id, Rc::new(f)));
+        ShaderChangedCallbackId {
+            id,
+            inner: Rc::downgrade(&self.inner),
+        }
+    }
+}
+
+impl Drop for ShaderWatcher {
+    fn drop(&mut self) {
+        if let Some(monitor) = self.inner.monitor.take() {
+            monitor.cancel();
+        }
+    }
+}
+
+impl fmt::Debug for ShaderWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ShaderWatcher")
+            .field("path", &self.inner.path)
+            .field("shader", &*self.inner.shader.borrow())
+            .field("error", &*self.inner.error.borrow())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_line() {
+        // Mesa
+        assert_eq!(error_line("0:12(5): error: `foo' undeclared"), Some(12));
+        // NVIDIA
+        assert_eq!(
+            error_line("0(12) : error C1008: undefined variable \"foo\""),
+            Some(12)
+        );
+        // ANGLE, macOS and others
+        assert_eq!(
+            error_line("ERROR: 0:12: 'foo' : undeclared identifier"),
+            Some(12)
+        );
+        assert_eq!(error_line("ERROR: 10:12: not the first string"), None);
+        assert_eq!(error_line("Could not open file"), None);
+        assert_eq!(error_line("0:(5): error"), None);
+    }
+
+    #[test]
+    fn test_reload_callbacks() {
+        let path =
+            std::env::temp_dir().join(format!("gsk4-shader-watcher-{}.glsl", std::process::id()));
+        let source =
+            "void mainImage(out vec4 c, in vec2 f, in vec2 r, in vec2 u) { c = vec4(1); }\n";
+        std::fs::write(&path, source).unwrap();
+        let watcher = ShaderWatcher::new(&path, None).unwrap();
+
+        let reloads = Rc::new(Cell::new(0));
+        let reloads_clone = reloads.clone();
+        let id = watcher.connect_changed(move |result| {
+            assert!(result.is_ok());
+            reloads_clone.set(reloads_clone.get() + 1);
+        });
+
+        // A file created again with the same content reports both events.
+        watcher.inner.file_changed(gio::FileMonitorEvent::Created);
+        watcher
+            .inner
+            .file_changed(gio::FileMonitorEvent::ChangesDoneHint);
+        assert_eq!(reloads.get(), 0);
+
+        std::fs::write(&path, source.replace("vec4(1)", "vec4(0)")).unwrap();
+        watcher.inner.file_changed(gio::FileMonitorEvent::Created);
+        watcher
+            .inner
+            .file_changed(gio::FileMonitorEvent::ChangesDoneHint);
+        assert_eq!(reloads.get(), 1);
+
+        watcher.reload();
+        assert_eq!(reloads.get(), 2);
+
+        id.remove();
+        watcher.reload();
+        assert_eq!(reloads.get(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -2,6 +2,9 @@
 
 use crate::Transform;
 use glib::translate::*;
+use graphene::{Quaternion, Vec3, Vec4};
+use std::ops::{Mul, MulAssign};
+use std::ptr;
 
 impl Transform {
     #[doc(alias = "gsk_transform_parse")]
@@ -16,12 +19,217 @@ impl Transform {
             if ret {
                 Ok(from_glib_full(out_transform))
             } else {
-                glib::bool_error!("Can't parse Transform")
+                Err(glib::bool_error!("Can't parse Transform"))
             }
         }
     }
 }
 
+// rustdoc-stripper-ignore-next
+/// The components of a [`Transform`](crate::Transform), as returned by
+/// [`Transform::decompose`](crate::Transform::decompose).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decomposed {
+    pub translate: Vec3,
+    pub scale: Vec3,
+    pub rotate: Quaternion,
+    // rustdoc-stripper-ignore-next
+    /// The shear factors, in the order XY, XZ and YZ.
+    pub skew: Vec3,
+    pub perspective: Vec4,
+}
+
+// A single function of the CSS form of a transform, like `translate(1, 2)`.
+#[derive(Debug, Clone, PartialEq)]
+struct Function {
+    name: String,
+    args: Vec<f32>,
+}
+
+impl Function {
+    // The function of the same type that leaves points unchanged, if any.
+    fn identity(&self) -> Option<Self> {
+        let args = match self.name.as_str() {
+            "translate" | "translate3d" | "rotate" | "skew" | "skewX" | "skewY" => {
+                vec![0.0; self.args.len()]
+            }
+            "scale" | "scale3d" => vec![1.0; self.args.len()],
+            "rotate3d" => {
+                let mut args = self.args.clone();
+                *args.last_mut()? = 0.0;
+                args
+            }
+            _ => return None,
+        };
+        Some(Self {
+            name: self.name.clone(),
+            args,
+        })
+    }
+}
+
+// Splits the CSS form of a transform into its functions.
+fn functions(transform: &Transform) -> Option<Vec<Function>> {
+    let string = transform.to_str();
+    let string = string.trim();
+    if string == "none" {
+        return Some(Vec::new());
+    }
+    string
+        .split(')')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let (name, args) = part.split_once('(')?;
+            let args = args
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| arg.parse().ok())
+                .collect::<Option<Vec<f32>>>()?;
+            Some(Function {
+                name: name.trim().to_owned(),
+                args,
+            })
+        })
+        .collect()
+}
+
+impl Transform {
+    // rustdoc-stripper-ignore-next
+    /// Decomposes the matrix of the transform into its components, or returns
+    /// `None` if the matrix can't be decomposed, like when it is singular.
+    #[doc(alias = "graphene_matrix_decompose")]
+    pub fn decompose(&self) -> Option<Decomposed> {
+        let (translate, scale, rotate, skew, perspective) = self.to_matrix().decompose()?;
+        Some(Decomposed {
+            translate,
+            scale,
+            rotate,
+            skew,
+            perspective,
+        })
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Interpolates between `from` and `to`, following the CSS rules for
+    /// interpolating transforms.
+    ///
+    /// When both transforms are made of the same functions, like
+    /// `translate(0, 0) rotate(0)` and `translate(10, 0) rotate(90)`, the
+    /// arguments of each function are interpolated, with the shorter list padded
+    /// with identity functions. Otherwise, the matrices of the transforms are
+    /// decomposed and their components are interpolated.
+    pub fn interpolate(from: &Transform, to: &Transform, progress: f64) -> Transform {
+        skip_assert_initialized!();
+        Self::interpolate_functions(from, to, progress as f32).unwrap_or_else(|| {
+            let matrix = from.to_matrix().interpolate(&to.to_matrix(), progress);
+            Transform::new().matrix(&matrix).unwrap_or_default()
+        })
+    }
+
+    fn interpolate_functions(from: &Transform, to: &Transform, progress: f32) -> Option<Transform> {
+        let mut from_functions = functions(from)?;
+        let mut to_functions = functions(to)?;
+        while from_functions.len() < to_functions.len() {
+            from_functions.push(to_functions[from_functions.len()].identity()?);
+        }
+        while to_functions.len() < from_functions.len() {
+            to_functions.push(from_functions[to_functions.len()].identity()?);
+        }
+
+        let mut string = String::new();
+        for (a, b) in from_functions.iter().zip(&to_functions) {
+            if a.name != b.name || a.args.len() != b.args.len() {
+                return None;
+            }
+            match a.name.as_str() {
+                // These are interpolated through their decomposed matrices.
+                "matrix" | "matrix3d" | "perspective" => return None,
+                "rotate3d" if a.args[..3] != b.args[..3] => return None,
+                _ => (),
+            }
+            let args = a
+                .args
+                .iter()
+                .zip(&b.args)
+                .map(|(a, b)| (a + (b - a) * progress).to_string())
+                .collect::<Vec<_>>();
+            string.push_str(&format!("{}({}) ", a.name, args.join(", ")));
+        }
+
+        if string.is_empty() {
+            Some(Transform::new())
+        } else {
+            Transform::parse(string.trim_end()).ok()
+        }
+    }
+}
+
+impl Mul for Transform {
+    type Output = Transform;
+
+    // rustdoc-stripper-ignore-next
+    /// Applies `rhs` after `self`, like writing both transforms one after the
+    /// other in CSS.
+    fn mul(self, rhs: Transform) -> Self::Output {
+        &self * &rhs
+    }
+}
+
+impl<'a> Mul<&'a Transform> for Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: &'a Transform) -> Self::Output {
+        &self * rhs
+    }
+}
+
+impl<'a> Mul<Transform> for &'a Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: Transform) -> Self::Output {
+        self * &rhs
+    }
+}
+
+impl<'a, 'b> Mul<&'b Transform> for &'a Transform {
+    type Output = Transform;
+
+    fn mul(self, rhs: &'b Transform) -> Self::Output {
+        self.transform(Some(rhs)).unwrap_or_default()
+    }
+}
+
+impl MulAssign for Transform {
+    fn mul_assign(&mut self, rhs: Transform) {
+        *self = &*self * &rhs;
+    }
+}
+
+impl<'a> MulAssign<&'a Transform> for Transform {
+    fn mul_assign(&mut self, rhs: &'a Transform) {
+        *self = &*self * rhs;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Transform {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Transform {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let string = String::deserialize(deserializer)?;
+        match string.trim() {
+            "" | "none" => Ok(Transform::new()),
+            string => Transform::parse(string).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
 impl std::str::FromStr for Transform {
     type Err = glib::BoolError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -29,3 +237,142 @@ impl std::str::FromStr for Transform {
         Transform::parse(s)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphene::{Matrix, Point};
+
+    fn assert_matrix_eq(transform: &Transform, expected: &Transform) {
+        let (a, b) = (
+            transform.to_matrix().to_float(),
+            expected.to_matrix().to_float(),
+        );
+        assert!(
+            a.iter().zip(&b).all(|(a, b)| (a - b).abs() < 1e-4),
+            "{} != {}",
+            transform,
+            expected
+        );
+    }
+
+    fn translate(x: f32, y: f32) -> Transform {
+        Transform::new().translate(&Point::new(x, y)).unwrap()
+    }
+
+    #[test]
+    fn test_decompose() {
+        let identity = Transform::new().decompose().unwrap();
+        assert_eq!(identity.translate, Vec3::zero());
+        assert_eq!(identity.scale, Vec3::one());
+        assert_eq!(identity.skew, Vec3::zero());
+
+        let decomposed = translate(3.0, 4.0).decompose().unwrap();
+        assert_eq!(decomposed.translate, Vec3::new(3.0, 4.0, 0.0));
+
+        let decomposed = Transform::new()
+            .scale(2.0, 3.0)
+            .unwrap()
+            .decompose()
+            .unwrap();
+        assert_eq!(decomposed.scale, Vec3::new(2.0, 3.0, 1.0));
+
+        let decomposed = Transform::new().rotate(90.0).unwrap().decompose().unwrap();
+        let (_, _, z) = decomposed.rotate.to_angles();
+        assert!((z - 90.0).abs() < 1e-3, "{}", z);
+
+        assert!(Transform::new()
+            .scale(0.0, 1.0)
+            .unwrap()
+            .decompose()
+            .is_none());
+    }
+
+    #[test]
+    fn test_interpolate_functions() {
+        let from = translate(2.0, 0.0);
+        let to: Transform = "translate(10, 0) rotate(90)".parse().unwrap();
+        assert_matrix_eq(&Transform::interpolate(&from, &to, 0.0), &from);
+        assert_matrix_eq(&Transform::interpolate(&from, &to, 1.0), &to);
+        // The missing rotation is interpolated from an identity rotation.
+        assert_matrix_eq(
+            &Transform::interpolate(&from, &to, 0.5),
+            &"translate(6, 0) rotate(45)".parse().unwrap(),
+        );
+
+        // Identity transforms are interpolated like their counterpart.
+        assert_matrix_eq(
+            &Transform::interpolate(&Transform::new(), &to, 0.5),
+            &"translate(5, 0) rotate(45)".parse().unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_interpolate_rotate() {
+        // Angles are interpolated as numbers, so going from 170 to -170 turns
+        // through 0 rather than through 180, like CSS does.
+        let from = Transform::new().rotate(170.0).unwrap();
+        let to = Transform::new().rotate(-170.0).unwrap();
+        assert_matrix_eq(&Transform::interpolate(&from, &to, 0.5), &Transform::new());
+        assert_matrix_eq(
+            &Transform::interpolate(&from, &to, 0.25),
+            &Transform::new().rotate(85.0).unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_interpolate_matrix() {
+        // Different functions fall back to interpolating decomposed matrices.
+        let from = translate(10.0, 0.0);
+        let to = Transform::new().scale(2.0, 2.0).unwrap();
+        let halfway = Transform::interpolate(&from, &to, 0.5);
+        let point = halfway.transform_point(&Point::new(2.0, 0.0));
+        assert!((point.x() - 8.0).abs() < 1e-4, "{:?}", point);
+        assert!(point.y().abs() < 1e-4, "{:?}", point);
+
+        let matrix = Transform::new()
+            .matrix(&Matrix::from_2d(1.0, 0.0, 0.0, 1.0, 4.0, 0.0))
+            .unwrap();
+        assert_matrix_eq(
+            &Transform::interpolate(&Transform::new(), &matrix, 0.5),
+            &translate(2.0, 0.0),
+        );
+    }
+
+    #[test]
+    fn test_mul() {
+        let a = translate(1.0, 0.0);
+        let b = Transform::new().scale(2.0, 2.0).unwrap();
+        // The scale applies first, then the translation.
+        let expected: Transform = "translate(1, 0) scale(2)".parse().unwrap();
+        assert_matrix_eq(&(&a * &b), &expected);
+        assert_matrix_eq(&(a.clone() * &b), &expected);
+        assert_matrix_eq(&(&a * b.clone()), &expected);
+        assert_matrix_eq(&(a.clone() * b.clone()), &expected);
+        assert_eq!(
+            (&a * &b).transform_point(&Point::new(1.0, 0.0)),
+            Point::new(3.0, 0.0)
+        );
+
+        let mut c = a.clone();
+        c *= &b;
+        assert_matrix_eq(&c, &expected);
+        let mut c = a;
+        c *= b;
+        assert_matrix_eq(&c, &expected);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let transform: Transform = "translate(1, 2) rotate(45) scale(2, 3)".parse().unwrap();
+        let json = serde_json::to_string(&transform).unwrap();
+        let deserialized: Transform = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.to_str(), transform.to_str());
+        assert_matrix_eq(&deserialized, &transform);
+
+        let identity: Transform = serde_json::from_str("\"none\"").unwrap();
+        assert_matrix_eq(&identity, &Transform::new());
+        assert!(serde_json::from_str::<Transform>("\"rotate(\"").is_err());
+    }
+}
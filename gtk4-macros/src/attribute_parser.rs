@@ -80,11 +80,13 @@ fn parse_attribute(meta: &NestedMeta) -> Result<(String, String)> {
 pub enum FieldAttributeArg {
     Id(String),
     Internal(bool),
+    Name(String),
 }
 
 #[derive(Debug)]
 pub enum FieldAttributeType {
     TemplateChild,
+    Uniform,
 }
 
 pub struct FieldAttribute {
@@ -158,6 +160,10 @@ fn parse_field_attr_meta(
             "internal" => FieldAttributeArg::Internal(parse_field_attr_value_bool(name_value)?),
             _ => return unknown_err,
         },
+        FieldAttributeType::Uniform => match ident_str.as_str() {
+            "name" => FieldAttributeArg::Name(parse_field_attr_value_str(name_value)?),
+            _ => return unknown_err,
+        },
     };
 
     Ok(value)
@@ -212,6 +218,8 @@ fn parse_field(field: &Field) -> Result<Option<AttributedField>, Error> {
         let path_span = field_attr.path.span();
         let ty = if field_attr.path.is_ident("template_child") {
             Some(FieldAttributeType::TemplateChild)
+        } else if field_attr.path.is_ident("uniform") {
+            Some(FieldAttributeType::Uniform)
         } else {
             None
         };
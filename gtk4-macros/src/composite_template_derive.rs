@@ -44,6 +44,7 @@ fn gen_template_child_bindings(fields: &syn::Fields) -> TokenStream {
                 FieldAttributeArg::Internal(internal) => {
                     value_internal = *internal;
                 }
+                FieldAttributeArg::Name(_) => (),
             });
 
             quote! {
@@ -54,6 +55,10 @@ fn gen_template_child_bindings(fields: &syn::Fields) -> TokenStream {
                 );
             }
         }
+        FieldAttributeType::Uniform => abort!(
+            field.attr.path_span,
+            "the `uniform` attribute is only supported by derive(ShaderArgs)"
+        ),
     });
 
     quote! {
@@ -6,6 +6,7 @@
 
 mod attribute_parser;
 mod composite_template_derive;
+mod shader_args_derive;
 mod util;
 
 use proc_macro::TokenStream;
@@ -90,3 +91,114 @@ pub fn composite_template_derive(input: TokenStream) -> TokenStream {
     let gen = composite_template_derive::impl_composite_template(&input);
     gen.into()
 }
+
+/// Derive macro for mapping the fields of a struct to the uniforms of a
+/// [`gsk::GLShader`](../gsk4/struct.GLShader.html).
+///
+/// Every field maps to the uniform of the same name, unless a different name is
+/// given with `#[uniform(name = "...")]`. Fields can be of type `f32`, `i32`,
+/// `u32`, `bool`, `graphene::Vec2`, `graphene::Vec3` or `graphene::Vec4`.
+/// GSK reserves the uniform names starting with `u_` or `gsk_`, so fields can't
+/// map to them.
+///
+/// The fields are checked against the uniforms declared by the shader source when
+/// creating a `gsk::TypedGLShader`, instead of at render time.
+///
+/// # Example
+///
+/// ```no_run
+/// use gtk::prelude::*;
+/// use gtk::{graphene, gsk};
+///
+/// #[derive(gtk::ShaderArgs)]
+/// struct Wave {
+///     time: f32,
+///     #[uniform(name = "wave_amplitude")]
+///     amplitude: f32,
+///     center: graphene::Vec2,
+/// }
+///
+/// fn snapshot(
+///     widget: &gtk::Widget,
+///     child: &gtk::Widget,
+///     snapshot: &gtk::Snapshot,
+///     shader: &gsk::TypedGLShader<Wave>,
+/// ) {
+///     let bounds = graphene::Rect::new(0.0, 0.0, 100.0, 100.0);
+///     snapshot
+///         .push_typed_gl_shader(
+///             shader,
+///             &bounds,
+///             &Wave {
+///                 time: 1.0,
+///                 amplitude: 0.2,
+///                 center: graphene::Vec2::new(50.0, 50.0),
+///             },
+///         )
+///         .expect("The uniforms were checked when creating the shader");
+///     widget.snapshot_child(child, snapshot);
+///     snapshot.gl_shader_pop_texture();
+///     snapshot.pop();
+/// }
+/// ```
+///
+/// The uniforms are checked by name and type against the shader source:
+///
+/// ```
+/// use gtk::{glib, graphene, gsk};
+/// use gsk::{GLUniformType, ShaderArgs};
+///
+/// #[derive(gtk::ShaderArgs)]
+/// struct Wave {
+///     time: f32,
+///     #[uniform(name = "wave_amplitude")]
+///     amplitude: f32,
+///     center: graphene::Vec2,
+/// }
+///
+/// assert_eq!(
+///     Wave::UNIFORMS,
+///     [
+///         ("time", GLUniformType::Float),
+///         ("wave_amplitude", GLUniformType::Float),
+///         ("center", GLUniformType::Vec2),
+///     ]
+/// );
+///
+/// let shader = |source: &'static str| {
+///     gsk::GLShader::from_bytes(&glib::Bytes::from_static(source.as_bytes()))
+/// };
+/// let matching = shader(
+///     "uniform float time;\nuniform float wave_amplitude;\nuniform vec2 center;\n",
+/// );
+/// assert!(gsk::TypedGLShader::<Wave>::new(matching).is_ok());
+///
+/// let mismatched = shader(
+///     "uniform float time;\nuniform int wave_amplitude;\nuniform vec2 center;\n",
+/// );
+/// let err = gsk::TypedGLShader::<Wave>::new(mismatched).unwrap_err();
+/// assert!(err.to_string().contains("wave_amplitude"));
+/// ```
+///
+/// Fields of other types are rejected at compile time:
+///
+/// ```compile_fail
+/// #[derive(gtk::ShaderArgs)]
+/// struct Label {
+///     text: String,
+/// }
+/// ```
+///
+/// Only structs with named fields are supported:
+///
+/// ```compile_fail
+/// #[derive(gtk::ShaderArgs)]
+/// struct Wave(f32, f32);
+/// ```
+#[proc_macro_derive(ShaderArgs, attributes(uniform))]
+#[proc_macro_error]
+pub fn shader_args_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let gen = shader_args_derive::impl_shader_args(&input);
+    gen.into()
+}
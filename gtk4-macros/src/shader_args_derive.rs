@@ -0,0 +1,64 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use proc_macro2::TokenStream;
+use proc_macro_error::{abort, abort_call_site};
+use quote::quote;
+use syn::{Data, Fields};
+
+use crate::attribute_parser::*;
+use crate::util::*;
+
+pub fn impl_shader_args(input: &syn::DeriveInput) -> TokenStream {
+    let name = &input.ident;
+    let crate_ident = crate_ident_new();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let struct_fields = match input.data {
+        Data::Struct(ref s) => &s.fields,
+        _ => abort_call_site!("derive(ShaderArgs) only supports structs"),
+    };
+    let fields = match struct_fields {
+        Fields::Named(fields) => &fields.named,
+        _ => abort_call_site!("derive(ShaderArgs) only supports structs with named fields"),
+    };
+
+    let attributed_fields = match parse_fields(struct_fields) {
+        Ok(fields) => fields,
+        Err(err) => abort!(err.span(), err),
+    };
+
+    let uniforms = fields.iter().map(|field| {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let mut uniform_name = ident.to_string();
+        if let Some(attributed) = attributed_fields.iter().find(|f| &f.ident == ident) {
+            for arg in &attributed.attr.args {
+                if let FieldAttributeArg::Name(value) = arg {
+                    uniform_name = value.clone();
+                }
+            }
+        }
+        quote! {
+            (#uniform_name, <#ty as #crate_ident::gsk::ShaderArg>::UNIFORM_TYPE)
+        }
+    });
+
+    let setters = fields.iter().enumerate().map(|(idx, field)| {
+        let ident = field.ident.as_ref().unwrap();
+        quote! {
+            #crate_ident::gsk::ShaderArg::set_arg(&self.#ident, builder, indices[#idx]);
+        }
+    });
+
+    quote! {
+        impl #impl_generics #crate_ident::gsk::ShaderArgs for #name #ty_generics #where_clause {
+            const UNIFORMS: &'static [(&'static str, #crate_ident::gsk::GLUniformType)] = &[
+                #(#uniforms),*
+            ];
+
+            fn set_args(&self, builder: &#crate_ident::gsk::ShaderArgsBuilder, indices: &[i32]) {
+                #(#setters)*
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::DragSource;
+use glib::{StaticType, ToValue};
+
+impl DragSource {
+    // rustdoc-stripper-ignore-next
+    /// Offers `payload` as the content of the drags started from this source.
+    ///
+    /// Drop targets in this process receive the value itself, so objects are
+    /// passed by reference instead of being copied. Receive it with
+    /// [`DropTarget::for_type`](crate::DropTarget::for_type).
+    pub fn set_payload<T: ToValue + StaticType>(&self, payload: &T) {
+        self.set_content(Some(&gdk::ContentProvider::for_value(&payload.to_value())));
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Like [`Self::set_payload`], but also offers `payload` as JSON under
+    /// `mime_type` for drop targets in other processes.
+    ///
+    /// Receive it with [`DropTarget::for_serde_type`](crate::DropTarget::for_serde_type).
+    #[cfg(feature = "json")]
+    #[cfg_attr(feature = "dox", doc(cfg(feature = "json")))]
+    pub fn set_serde_payload<T: ToValue + StaticType + serde::Serialize>(
+        &self,
+        mime_type: &str,
+        payload: &T,
+    ) -> Result<(), glib::Error> {
+        self.set_serde_payload_with::<gdk::JsonCodec, T>(mime_type, payload)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Like [`Self::set_serde_payload`], encoding the payload with `C`.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(feature = "dox", doc(cfg(feature = "serde")))]
+    pub fn set_serde_payload_with<
+        C: gdk::ContentCodec,
+        T: ToValue + StaticType + serde::Serialize,
+    >(
+        &self,
+        mime_type: &str,
+        payload: &T,
+    ) -> Result<(), glib::Error> {
+        let data = C::encode(payload)
+            .map_err(|err| glib::Error::new(gio::IOErrorEnum::InvalidData, &err.to_string()))?;
+        let provider = gdk::ContentProvider::new_union(&[
+            gdk::ContentProvider::for_value(&payload.to_value()),
+            gdk::ContentProvider::for_bytes(mime_type, &glib::Bytes::from_owned(data)),
+        ]);
+        self.set_content(Some(&provider));
+        Ok(())
+    }
+}
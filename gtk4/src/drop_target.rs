@@ -1,5 +1,6 @@
 // Take a look at the license at the top of the repository in the LICENSE file.
 
+use crate::prelude::*;
 use crate::DropTarget;
 use glib::signal::connect_raw;
 use glib::Type;
@@ -7,7 +8,130 @@ use glib::{translate::*, value::FromValue, ObjectType, SignalHandlerId};
 use std::boxed::Box as Box_;
 use std::mem::transmute;
 
+// rustdoc-stripper-ignore-next
+/// Which side of a row a drop lands on, to reorder the items of a list.
+///
+/// ```no_run
+/// use gtk4 as gtk;
+/// use gtk::{gdk, gio, glib, prelude::*};
+///
+/// # fn make_reorderable(row: &gtk::Widget, position: u32, model: gio::ListStore) {
+/// let source = gtk::DragSource::new();
+/// source.set_payload(&position);
+/// row.add_controller(&source);
+///
+/// let target = gtk::DropTarget::for_type(gdk::DragAction::MOVE, move |target, from: u32, _, y| {
+///     let to = target.drop_position(y).move_position(from, position);
+///     if let Some(item) = model.item(from) {
+///         model.remove(from);
+///         model.insert(to, &item);
+///     }
+///     true
+/// });
+/// row.add_controller(&target);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DropPosition {
+    Before,
+    After,
+}
+
+impl DropPosition {
+    // rustdoc-stripper-ignore-next
+    /// The side of a row of `height` that a drop at `y` lands on.
+    pub fn for_row(y: f64, height: f64) -> Self {
+        skip_assert_initialized!();
+        if y < height / 2.0 {
+            Self::Before
+        } else {
+            Self::After
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The position in the model to insert a new item at, for a drop on the
+    /// row at `row`.
+    pub fn insert_position(self, row: u32) -> u32 {
+        match self {
+            Self::Before => row,
+            Self::After => row + 1,
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The position in the model to insert an item moved from `from` at,
+    /// after removing it, for a drop on the row at `row`.
+    pub fn move_position(self, from: u32, row: u32) -> u32 {
+        let position = self.insert_position(row);
+        if from < position {
+            position - 1
+        } else {
+            position
+        }
+    }
+}
+
 impl DropTarget {
+    // rustdoc-stripper-ignore-next
+    /// Creates a drop target accepting values of type `T`, calling `f` with the
+    /// dropped value.
+    ///
+    /// Values from a [`DragSource::set_payload`](crate::DragSource::set_payload)
+    /// in the same process are passed as they are, without being copied.
+    pub fn for_type<T, F>(actions: gdk::DragAction, f: F) -> Self
+    where
+        T: for<'a> FromValue<'a> + StaticType + 'static,
+        F: Fn(&DropTarget, T, f64, f64) -> bool + 'static,
+    {
+        assert_initialized_main_thread!();
+        let target = Self::new(T::static_type(), actions);
+        target.connect_drop(move |target, value, x, y| match value.get::<T>() {
+            Ok(value) => f(target, value, x, y),
+            Err(_) => false,
+        });
+        target
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Like [`Self::for_type`], but also accepts values sent as JSON under
+    /// `mime_type` by other processes, for example with
+    /// [`DragSource::set_serde_payload`](crate::DragSource::set_serde_payload).
+    #[cfg(feature = "json")]
+    #[cfg_attr(feature = "dox", doc(cfg(feature = "json")))]
+    pub fn for_serde_type<T, F>(mime_type: &str, actions: gdk::DragAction, f: F) -> Self
+    where
+        T: for<'a> FromValue<'a> + ToValue + StaticType + serde::de::DeserializeOwned + 'static,
+        F: Fn(&DropTarget, T, f64, f64) -> bool + 'static,
+    {
+        Self::for_serde_type_with::<gdk::JsonCodec, T, F>(mime_type, actions, f)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Like [`Self::for_serde_type`], decoding values with `C`.
+    ///
+    /// The decoder is registered with GDK once per type and MIME type, so
+    /// the first codec used for them is the one used for all drops.
+    #[cfg(feature = "serde")]
+    #[cfg_attr(feature = "dox", doc(cfg(feature = "serde")))]
+    pub fn for_serde_type_with<C, T, F>(mime_type: &str, actions: gdk::DragAction, f: F) -> Self
+    where
+        C: gdk::ContentCodec + 'static,
+        T: for<'a> FromValue<'a> + ToValue + StaticType + serde::de::DeserializeOwned + 'static,
+        F: Fn(&DropTarget, T, f64, f64) -> bool + 'static,
+    {
+        assert_initialized_main_thread!();
+        gdk::content_register_serde_deserializer::<C, T>(mime_type);
+        Self::for_type(actions, f)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The side of the widget of the drop target that a drop at `y` lands on.
+    pub fn drop_position(&self, y: f64) -> DropPosition {
+        let height = self.widget().map_or(0, |widget| widget.height());
+        DropPosition::for_row(y, height as f64)
+    }
+
     #[doc(alias = "gtk_drop_target_set_gtypes")]
     pub fn set_types(&self, types: &[Type]) {
         let types: Vec<glib::ffi::GType> = types.iter().map(|t| t.into_glib()).collect();
@@ -78,3 +202,27 @@ impl DropTarget {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_position() {
+        assert_eq!(DropPosition::for_row(4.0, 20.0), DropPosition::Before);
+        assert_eq!(DropPosition::for_row(10.0, 20.0), DropPosition::After);
+
+        assert_eq!(DropPosition::Before.insert_position(3), 3);
+        assert_eq!(DropPosition::After.insert_position(3), 4);
+
+        // Moving down, the item itself is removed before the target row.
+        assert_eq!(DropPosition::Before.move_position(1, 3), 2);
+        assert_eq!(DropPosition::After.move_position(1, 3), 3);
+        // Moving up, nothing before the target row changes.
+        assert_eq!(DropPosition::Before.move_position(5, 3), 3);
+        assert_eq!(DropPosition::After.move_position(5, 3), 4);
+        // Dropping on itself keeps the item in place.
+        assert_eq!(DropPosition::Before.move_position(3, 3), 3);
+        assert_eq!(DropPosition::After.move_position(3, 3), 3);
+    }
+}
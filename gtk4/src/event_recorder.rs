@@ -0,0 +1,803 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use crate::{
+    EventController, EventControllerFocus, EventControllerKey, EventControllerLegacy,
+    EventControllerMotion, EventControllerScroll, GestureClick, PickFlags, PropagationPhase,
+    Widget, Window,
+};
+use gdk::{EventType, ModifierType};
+use glib::translate::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+// rustdoc-stripper-ignore-next
+/// The kind specific part of a [`RecordedEvent`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub enum RecordedEventKind {
+    // rustdoc-stripper-ignore-next
+    /// A mouse button was pressed, `button` being 1 for the primary button.
+    ///
+    /// `n_press` is the number of presses in a row, counted with the double
+    /// click time and distance of the [`Settings`](crate::Settings) of the
+    /// toplevel, like [`GestureClick`] does.
+    ButtonPress {
+        button: u32,
+        n_press: u32,
+    },
+    // rustdoc-stripper-ignore-next
+    /// A mouse button was released, `n_press` being the one of the last press.
+    ButtonRelease {
+        button: u32,
+        n_press: u32,
+    },
+    // rustdoc-stripper-ignore-next
+    /// A key was pressed, with the raw value of its [`gdk::keys::Key`] and its
+    /// hardware keycode.
+    KeyPress {
+        keyval: u32,
+        keycode: u32,
+    },
+    KeyRelease {
+        keyval: u32,
+        keycode: u32,
+    },
+    Motion,
+    // rustdoc-stripper-ignore-next
+    /// The scroll deltas, for smooth scrolling and scroll wheels alike.
+    Scroll {
+        dx: f64,
+        dy: f64,
+    },
+    Enter,
+    Leave,
+    Focus {
+        is_in: bool,
+    },
+    TouchBegin,
+    TouchUpdate,
+    TouchEnd,
+    TouchCancel,
+    // rustdoc-stripper-ignore-next
+    /// Any other event, identified by the raw value of its [`gdk::EventType`].
+    Other {
+        event_type: i32,
+    },
+}
+
+impl RecordedEventKind {
+    fn from_event(event: &gdk::Event) -> Self {
+        if let Some(button) = event.downcast_ref::<gdk::ButtonEvent>() {
+            return match event.event_type() {
+                EventType::ButtonPress => Self::ButtonPress {
+                    button: button.button(),
+                    n_press: 1,
+                },
+                _ => Self::ButtonRelease {
+                    button: button.button(),
+                    n_press: 1,
+                },
+            };
+        }
+        if let Some(key) = event.downcast_ref::<gdk::KeyEvent>() {
+            let (keyval, keycode) = (key.keyval().into_glib(), key.keycode());
+            return match event.event_type() {
+                EventType::KeyPress => Self::KeyPress { keyval, keycode },
+                _ => Self::KeyRelease { keyval, keycode },
+            };
+        }
+        if let Some(scroll) = event.downcast_ref::<gdk::ScrollEvent>() {
+            let (dx, dy) = scroll.deltas();
+            return Self::Scroll { dx, dy };
+        }
+        if let Some(focus) = event.downcast_ref::<gdk::FocusEvent>() {
+            return Self::Focus {
+                is_in: focus.is_in(),
+            };
+        }
+        match event.event_type() {
+            EventType::MotionNotify => Self::Motion,
+            EventType::EnterNotify => Self::Enter,
+            EventType::LeaveNotify => Self::Leave,
+            EventType::TouchBegin => Self::TouchBegin,
+            EventType::TouchUpdate => Self::TouchUpdate,
+            EventType::TouchEnd => Self::TouchEnd,
+            EventType::TouchCancel => Self::TouchCancel,
+            event_type => Self::Other {
+                event_type: event_type.into_glib(),
+            },
+        }
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// The widget that was under the pointer when an event was recorded.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub struct RecordedTarget {
+    // rustdoc-stripper-ignore-next
+    /// Index of each ancestor among its siblings, from the toplevel down to the target.
+    pub path: Vec<u32>,
+    // rustdoc-stripper-ignore-next
+    /// Type name of the target widget.
+    pub type_name: String,
+    // rustdoc-stripper-ignore-next
+    /// The `widget-name` of the target widget.
+    pub widget_name: String,
+    // rustdoc-stripper-ignore-next
+    /// Position of the event relative to the target widget.
+    pub position: (f64, f64),
+}
+
+impl RecordedTarget {
+    fn new(toplevel: &Widget, widget: &Widget, x: f64, y: f64) -> Self {
+        let mut path = Vec::new();
+        let mut current = widget.clone();
+        while &current != toplevel {
+            let parent = match current.parent() {
+                Some(parent) => parent,
+                None => break,
+            };
+            let mut index = 0;
+            let mut sibling = current.prev_sibling();
+            while let Some(s) = sibling {
+                index += 1;
+                sibling = s.prev_sibling();
+            }
+            path.push(index);
+            current = parent;
+        }
+        path.reverse();
+
+        Self {
+            path,
+            type_name: widget.type_().name().to_owned(),
+            widget_name: widget.widget_name().to_string(),
+            position: (x, y),
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Finds the target in the widget tree of `toplevel`.
+    ///
+    /// Returns `None` if the tree no longer has the same shape, or if the widget
+    /// found at the recorded path is not of the recorded type.
+    pub fn resolve(&self, toplevel: &impl IsA<Widget>) -> Option<Widget> {
+        let mut widget = toplevel.as_ref().clone();
+        for &index in &self.path {
+            let mut child = widget.first_child();
+            for _ in 0..index {
+                child = child?.next_sibling();
+            }
+            widget = child?;
+        }
+        if widget.type_().name() == self.type_name {
+            Some(widget)
+        } else {
+            None
+        }
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// A [`gdk::Event`] recorded by an [`EventRecorder`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub struct RecordedEvent {
+    // rustdoc-stripper-ignore-next
+    /// Milliseconds elapsed since the first recorded event.
+    pub time: u32,
+    // rustdoc-stripper-ignore-next
+    /// What happened, with the data specific to the type of event.
+    pub kind: RecordedEventKind,
+    // rustdoc-stripper-ignore-next
+    /// Raw bits of the [`gdk::ModifierType`] of the event.
+    pub modifiers: u32,
+    // rustdoc-stripper-ignore-next
+    /// Position of the event relative to the toplevel, if it has one.
+    pub position: Option<(f64, f64)>,
+    // rustdoc-stripper-ignore-next
+    /// The widget under the pointer, for events with a position.
+    pub target: Option<RecordedTarget>,
+}
+
+impl RecordedEvent {
+    // rustdoc-stripper-ignore-next
+    /// The modifier keys and mouse buttons that were held during the event.
+    pub fn modifier_state(&self) -> ModifierType {
+        ModifierType::from_bits_truncate(self.modifiers)
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Emulates the event on the event controllers of the widget tree of
+    /// `toplevel`, and returns whether any controller received it.
+    ///
+    /// GDK does not allow creating events, so this does not go through the
+    /// event delivery of GTK. Instead, it emits the signals that the
+    /// controllers listed below emit when they handle a real event:
+    ///
+    /// - key presses and releases go to the [`EventControllerKey`]s of the
+    ///   focus widget and its ancestors, until one handles the press;
+    /// - focus changes go to the [`EventControllerFocus`]s of the focus widget
+    ///   and its ancestors;
+    /// - button presses and releases, with their recorded `n_press`, go to the
+    ///   [`GestureClick`]s of the target, or of its closest ancestor with a
+    ///   gesture for that button. Touch begins and ends are handled like
+    ///   presses and releases of the primary button, and touch cancels stop
+    ///   the gestures;
+    /// - scrolls go to the [`EventControllerScroll`]s of the target and its
+    ///   ancestors, until one handles the scroll;
+    /// - motions, enters and leaves go to the [`EventControllerMotion`]s of the
+    ///   target and all its ancestors.
+    ///
+    /// Positions are relative to the recorded target when it is still found in
+    /// the widget tree, and to the toplevel otherwise.
+    ///
+    /// As the signals are emitted directly, the propagation phases, the state
+    /// machines of gestures and the claiming of event sequences are bypassed,
+    /// and other controllers, like drag gestures, never see the event. Touch
+    /// updates and other events are not emulated. Input methods only see real
+    /// events, so text typed in entries is not reproduced by key presses.
+    pub fn inject(&self, toplevel: &impl IsA<Window>) -> bool {
+        let window = toplevel.as_ref();
+        let toplevel = window.upcast_ref::<Widget>();
+        let focus = window.focus().unwrap_or_else(|| toplevel.clone());
+        let state = self.modifier_state();
+        match self.kind {
+            RecordedEventKind::KeyPress { keyval, keycode } => {
+                let mut received = false;
+                for widget in ancestors(focus) {
+                    for controller in controllers::<EventControllerKey>(&widget) {
+                        received = true;
+                        if controller
+                            .emit_by_name::<bool>("key-pressed", &[&keyval, &keycode, &state])
+                        {
+                            return true;
+                        }
+                    }
+                }
+                received
+            }
+            RecordedEventKind::KeyRelease { keyval, keycode } => {
+                let mut received = false;
+                for widget in ancestors(focus) {
+                    for controller in controllers::<EventControllerKey>(&widget) {
+                        received = true;
+                        controller.emit_by_name::<()>("key-released", &[&keyval, &keycode, &state]);
+                    }
+                }
+                received
+            }
+            RecordedEventKind::Focus { is_in } => {
+                let signal = if is_in { "enter" } else { "leave" };
+                let mut received = false;
+                for widget in ancestors(focus) {
+                    for controller in controllers::<EventControllerFocus>(&widget) {
+                        received = true;
+                        controller.emit_by_name::<()>(signal, &[]);
+                    }
+                }
+                received
+            }
+            RecordedEventKind::ButtonPress { button, n_press } => {
+                self.emit_click(toplevel, button, "pressed", Some(n_press))
+            }
+            RecordedEventKind::ButtonRelease { button, n_press } => {
+                self.emit_click(toplevel, button, "released", Some(n_press))
+            }
+            RecordedEventKind::TouchBegin => {
+                self.emit_click(toplevel, gdk::BUTTON_PRIMARY, "pressed", Some(1))
+            }
+            RecordedEventKind::TouchEnd => {
+                self.emit_click(toplevel, gdk::BUTTON_PRIMARY, "released", Some(1))
+            }
+            RecordedEventKind::TouchCancel => {
+                self.emit_click(toplevel, gdk::BUTTON_PRIMARY, "stopped", None)
+            }
+            RecordedEventKind::Scroll { dx, dy } => {
+                let mut received = false;
+                let (target, _) = match self.pointer_target(toplevel) {
+                    Some(target) => target,
+                    None => return false,
+                };
+                for widget in ancestors(target) {
+                    for controller in controllers::<EventControllerScroll>(&widget) {
+                        received = true;
+                        if controller.emit_by_name::<bool>("scroll", &[&dx, &dy]) {
+                            return true;
+                        }
+                    }
+                }
+                received
+            }
+            RecordedEventKind::Motion | RecordedEventKind::Enter | RecordedEventKind::Leave => {
+                let mut received = false;
+                let (target, (x, y)) = match self.pointer_target(toplevel) {
+                    Some(target) => target,
+                    None => return false,
+                };
+                for widget in ancestors(target.clone()) {
+                    let position = target.translate_coordinates(&widget, x, y);
+                    for controller in controllers::<EventControllerMotion>(&widget) {
+                        received = true;
+                        match (&self.kind, position) {
+                            (RecordedEventKind::Leave, _) => {
+                                controller.emit_by_name::<()>("leave", &[]);
+                            }
+                            (RecordedEventKind::Enter, Some((x, y))) => {
+                                controller.emit_by_name::<()>("enter", &[&x, &y]);
+                            }
+                            (_, Some((x, y))) => {
+                                controller.emit_by_name::<()>("motion", &[&x, &y]);
+                            }
+                            _ => (),
+                        }
+                    }
+                }
+                received
+            }
+            _ => false,
+        }
+    }
+
+    // Emits `signal` on the click gestures for `button` of the target or of
+    // its closest ancestor that has some, with `n_press` and the position for
+    // the signals that take them.
+    fn emit_click(
+        &self,
+        toplevel: &Widget,
+        button: u32,
+        signal: &str,
+        n_press: Option<u32>,
+    ) -> bool {
+        let (target, (x, y)) = match self.pointer_target(toplevel) {
+            Some(target) => target,
+            None => return false,
+        };
+        for widget in ancestors(target.clone()) {
+            let gestures: Vec<GestureClick> = controllers::<GestureClick>(&widget)
+                .into_iter()
+                .filter(|gesture| gesture.button() == 0 || gesture.button() == button)
+                .collect();
+            if gestures.is_empty() {
+                continue;
+            }
+            let (x, y) = match target.translate_coordinates(&widget, x, y) {
+                Some(position) => position,
+                None => return false,
+            };
+            for gesture in gestures {
+                match n_press {
+                    Some(n_press) => {
+                        gesture.emit_by_name::<()>(signal, &[&(n_press as i32), &x, &y])
+                    }
+                    None => gesture.emit_by_name::<()>(signal, &[]),
+                }
+            }
+            return true;
+        }
+        false
+    }
+
+    // The widget under the pointer and the position of the event relative to
+    // it, from the recorded target or else from the position of the event.
+    fn pointer_target(&self, toplevel: &Widget) -> Option<(Widget, (f64, f64))> {
+        if let Some(target) = &self.target {
+            if let Some(widget) = target.resolve(toplevel) {
+                return Some((widget, target.position));
+            }
+        }
+        let (x, y) = self.position?;
+        let widget = toplevel.pick(x, y, PickFlags::DEFAULT)?;
+        let position = toplevel.translate_coordinates(&widget, x, y)?;
+        Some((widget, position))
+    }
+}
+
+fn ancestors(widget: Widget) -> Vec<Widget> {
+    let mut widgets = vec![widget];
+    while let Some(parent) = widgets.last().unwrap().parent() {
+        widgets.push(parent);
+    }
+    widgets
+}
+
+fn controllers<T: IsA<EventController>>(widget: &Widget) -> Vec<T> {
+    let controllers = widget.observe_controllers();
+    (0..controllers.n_items())
+        .filter_map(|i| controllers.item(i)?.downcast::<T>().ok())
+        .collect()
+}
+
+// rustdoc-stripper-ignore-next
+/// A sequence of events recorded on a toplevel, in the order they were delivered.
+///
+/// With the `serde` feature, recordings can be stored in any format supported
+/// by serde, like JSON or RON.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(crate = "serde")
+)]
+pub struct EventRecording {
+    // rustdoc-stripper-ignore-next
+    /// The recorded events, oldest first.
+    pub events: Vec<RecordedEvent>,
+}
+
+impl EventRecording {
+    // rustdoc-stripper-ignore-next
+    /// Emulates the recorded events in `toplevel` from the main loop, with
+    /// the same timing as when they were recorded.
+    ///
+    /// The target of each event is resolved in the widget tree of `toplevel`
+    /// at the time the event is replayed. The events are not delivered like
+    /// real events, but by emitting the signals of the matching controllers,
+    /// see [`RecordedEvent::inject`] for what is and is not reproduced.
+    ///
+    /// ```no_run
+    /// use gtk4 as gtk;
+    /// use std::time::Duration;
+    ///
+    /// # fn example(window: &gtk::Window, recording: gtk::EventRecording) {
+    /// recording.replay(window);
+    /// let duration = recording.events.last().map_or(0, |event| event.time);
+    /// let _ = gtk::test::wait_for(|| false, Duration::from_millis(duration as u64 + 100));
+    /// # }
+    /// ```
+    pub fn replay(&self, toplevel: &impl IsA<Window>) {
+        self.replay_with(toplevel, |_, _| ());
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Like [`Self::replay`], but also hands the events that no controller
+    /// received to `handler`, with their resolved target.
+    ///
+    /// The handler can reproduce the effect of those events, for example by
+    /// activating the target widget or by calling the relevant model methods.
+    pub fn replay_with<F: Fn(&RecordedEvent, Option<&Widget>) + 'static>(
+        &self,
+        toplevel: &impl IsA<Window>,
+        handler: F,
+    ) {
+        let handler = Rc::new(handler);
+        let toplevel = toplevel.as_ref().downgrade();
+        for event in self.events.iter().cloned() {
+            let handler = handler.clone();
+            let toplevel = toplevel.clone();
+            glib::timeout_add_local_once(Duration::from_millis(event.time as u64), move || {
+                if let Some(toplevel) = toplevel.upgrade() {
+                    if !event.inject(&toplevel) {
+                        let target = event.target.as_ref().and_then(|t| t.resolve(&toplevel));
+                        handler(&event, target.as_ref());
+                    }
+                }
+            });
+        }
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Records every [`gdk::Event`] delivered to a toplevel.
+///
+/// The events are intercepted in the capture phase, before any other
+/// controller can handle them, and are never stopped.
+///
+/// ```no_run
+/// use gtk4 as gtk;
+///
+/// gtk::init().unwrap();
+/// let window = gtk::Window::new();
+/// let recorder = gtk::EventRecorder::new(&window);
+/// // ... interact with the window ...
+/// let recording = recorder.stop();
+/// println!("{} events recorded", recording.events.len());
+/// ```
+#[derive(Debug)]
+pub struct EventRecorder {
+    toplevel: Window,
+    controller: EventControllerLegacy,
+    events: Rc<RefCell<Vec<RecordedEvent>>>,
+}
+
+impl EventRecorder {
+    pub fn new(toplevel: &impl IsA<Window>) -> Self {
+        assert_initialized_main_thread!();
+        let toplevel = toplevel.as_ref().clone();
+        let events = Rc::new(RefCell::new(Vec::<RecordedEvent>::new()));
+        let start_time = RefCell::new(None);
+        let clicks = RefCell::new(ClickCounter::default());
+
+        let controller = EventControllerLegacy::new();
+        controller.set_propagation_phase(PropagationPhase::Capture);
+        let events_clone = events.clone();
+        let window = toplevel.downgrade();
+        controller.connect_event(move |_, event| {
+            let window = match window.upgrade() {
+                Some(window) => window,
+                None => return glib::signal::Inhibit(false),
+            };
+            let start = *start_time.borrow_mut().get_or_insert(event.time());
+
+            let position = event.position().map(|(x, y)| {
+                let (dx, dy) = window.surface_transform();
+                (x - dx, y - dy)
+            });
+            let widget = window.upcast_ref::<Widget>();
+            let target = position.and_then(|(x, y)| {
+                let picked = widget.pick(x, y, PickFlags::DEFAULT)?;
+                let (px, py) = widget.translate_coordinates(&picked, x, y)?;
+                Some(RecordedTarget::new(widget, &picked, px, py))
+            });
+
+            let mut kind = RecordedEventKind::from_event(event);
+            match &mut kind {
+                RecordedEventKind::ButtonPress { button, n_press } => {
+                    let settings = widget.settings();
+                    *n_press = clicks.borrow_mut().press(
+                        *button,
+                        event.time(),
+                        position.unwrap_or_default(),
+                        settings.gtk_double_click_time() as u32,
+                        settings.gtk_double_click_distance() as f64,
+                    );
+                }
+                RecordedEventKind::ButtonRelease { button, n_press } => {
+                    *n_press = clicks.borrow().release(*button);
+                }
+                _ => (),
+            }
+
+            events_clone.borrow_mut().push(RecordedEvent {
+                time: event.time().wrapping_sub(start),
+                kind,
+                modifiers: event.modifier_state().bits(),
+                position,
+                target,
+            });
+            glib::signal::Inhibit(false)
+        });
+        toplevel.add_controller(&controller);
+
+        Self {
+            toplevel,
+            controller,
+            events,
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Stops recording and returns the recorded events.
+    ///
+    /// Dropping the recorder also stops recording, discarding the events.
+    pub fn stop(self) -> EventRecording {
+        EventRecording {
+            events: self.events.take(),
+        }
+    }
+}
+
+impl Drop for EventRecorder {
+    fn drop(&mut self) {
+        self.toplevel.remove_controller(&self.controller);
+    }
+}
+
+// Counts the presses in a row like `GestureClick` does: a press of the same
+// button within the double click time and distance of the previous one
+// continues the series.
+#[derive(Debug, Default)]
+struct ClickCounter {
+    last: Option<(u32, u32, (f64, f64))>,
+    n_press: u32,
+}
+
+impl ClickCounter {
+    fn press(
+        &mut self,
+        button: u32,
+        time: u32,
+        (x, y): (f64, f64),
+        max_time: u32,
+        max_distance: f64,
+    ) -> u32 {
+        self.n_press = match self.last {
+            Some((last_button, last_time, (last_x, last_y)))
+                if last_button == button
+                    && time.wrapping_sub(last_time) <= max_time
+                    && (x - last_x).abs() <= max_distance
+                    && (y - last_y).abs() <= max_distance =>
+            {
+                self.n_press + 1
+            }
+            _ => 1,
+        };
+        self.last = Some((button, time, (x, y)));
+        self.n_press
+    }
+
+    fn release(&self, button: u32) -> u32 {
+        match self.last {
+            Some((last_button, ..)) if last_button == button => self.n_press,
+            _ => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_synced;
+    use std::cell::Cell;
+
+    fn event(kind: RecordedEventKind, target: Option<RecordedTarget>) -> RecordedEvent {
+        RecordedEvent {
+            time: 0,
+            kind,
+            modifiers: ModifierType::CONTROL_MASK.bits(),
+            position: Some((10.0, 10.0)),
+            target,
+        }
+    }
+
+    #[test]
+    fn test_inject() {
+        test_synced(move || {
+            let window = Window::new();
+            let child = crate::Box::new(crate::Orientation::Vertical, 0);
+            window.set_child(Some(&child));
+
+            let pressed = Rc::new(Cell::new(None));
+            let key = EventControllerKey::new();
+            key.connect_key_pressed(glib::clone!(@strong pressed => move |_, keyval, _, state| {
+                pressed.set(Some((keyval, state)));
+                glib::signal::Inhibit(true)
+            }));
+            window.add_controller(&key);
+
+            let scrolled = Rc::new(Cell::new(None));
+            let scroll = EventControllerScroll::new(crate::EventControllerScrollFlags::BOTH_AXES);
+            scroll.connect_scroll(glib::clone!(@strong scrolled => move |_, dx, dy| {
+                scrolled.set(Some((dx, dy)));
+                glib::signal::Inhibit(true)
+            }));
+            child.add_controller(&scroll);
+
+            let keyval = *gdk::keys::constants::z;
+            assert!(
+                event(RecordedEventKind::KeyPress { keyval, keycode: 0 }, None).inject(&window)
+            );
+            assert_eq!(
+                pressed.get(),
+                Some((gdk::keys::constants::z, ModifierType::CONTROL_MASK))
+            );
+
+            let target = RecordedTarget::new(window.upcast_ref(), child.upcast_ref(), 10.0, 10.0);
+            assert_eq!(target.path, vec![0]);
+            assert!(
+                event(RecordedEventKind::Scroll { dx: 0.0, dy: 2.0 }, Some(target)).inject(&window)
+            );
+            assert_eq!(scrolled.get(), Some((0.0, 2.0)));
+
+            assert!(!event(RecordedEventKind::TouchBegin, None).inject(&window));
+            window.destroy();
+        });
+    }
+
+    #[test]
+    fn test_inject_click() {
+        test_synced(move || {
+            let window = Window::new();
+            let child = crate::Box::new(crate::Orientation::Vertical, 0);
+            child.set_margin_start(5);
+            window.set_child(Some(&child));
+
+            let pressed = Rc::new(Cell::new(None));
+            let gesture = GestureClick::new();
+            gesture.connect_pressed(glib::clone!(@strong pressed => move |_, n_press, x, y| {
+                pressed.set(Some((n_press, x, y)));
+            }));
+            child.add_controller(&gesture);
+
+            let mut target =
+                RecordedTarget::new(window.upcast_ref(), child.upcast_ref(), 10.0, 10.0);
+            target.position = (3.0, 4.0);
+            let press = RecordedEventKind::ButtonPress {
+                button: 1,
+                n_press: 2,
+            };
+            assert!(event(press, Some(target)).inject(&window));
+            assert_eq!(pressed.get(), Some((2, 3.0, 4.0)));
+            window.destroy();
+        });
+    }
+
+    #[test]
+    fn test_click_counter() {
+        let mut clicks = ClickCounter::default();
+        assert_eq!(clicks.press(1, 0, (0.0, 0.0), 400, 5.0), 1);
+        assert_eq!(clicks.release(1), 1);
+        assert_eq!(clicks.press(1, 300, (4.0, 0.0), 400, 5.0), 2);
+        assert_eq!(clicks.release(1), 2);
+        assert_eq!(clicks.press(1, 600, (4.0, 3.0), 400, 5.0), 3);
+        // Too late, too far, or another button: a new series starts.
+        assert_eq!(clicks.press(1, 1100, (4.0, 3.0), 400, 5.0), 1);
+        assert_eq!(clicks.press(1, 1200, (20.0, 3.0), 400, 5.0), 1);
+        assert_eq!(clicks.press(3, 1300, (20.0, 3.0), 400, 5.0), 1);
+        assert_eq!(clicks.release(1), 1);
+    }
+
+    #[test]
+    fn test_recorder_drop() {
+        test_synced(move || {
+            let window = Window::new();
+            let n_controllers = || window.observe_controllers().n_items();
+            let before = n_controllers();
+
+            let recorder = EventRecorder::new(&window);
+            assert_eq!(n_controllers(), before + 1);
+            drop(recorder);
+            assert_eq!(n_controllers(), before);
+
+            let recording = EventRecorder::new(&window).stop();
+            assert!(recording.events.is_empty());
+            assert_eq!(n_controllers(), before);
+            window.destroy();
+        });
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_recording_json() {
+        use gdk::ContentCodec;
+
+        let target = RecordedTarget {
+            path: vec![0, 2],
+            type_name: "GtkButton".to_owned(),
+            widget_name: "ok".to_owned(),
+            position: (1.5, 2.0),
+        };
+        let recording = EventRecording {
+            events: vec![
+                event(
+                    RecordedEventKind::ButtonPress {
+                        button: 1,
+                        n_press: 2,
+                    },
+                    Some(target),
+                ),
+                RecordedEvent {
+                    time: 16,
+                    kind: RecordedEventKind::KeyRelease {
+                        keyval: *gdk::keys::constants::a,
+                        keycode: 38,
+                    },
+                    modifiers: 0,
+                    position: None,
+                    target: None,
+                },
+                event(RecordedEventKind::Focus { is_in: false }, None),
+                event(RecordedEventKind::Other { event_type: 4 }, None),
+            ],
+        };
+
+        let json = gdk::JsonCodec::encode(&recording).unwrap();
+        let decoded: EventRecording = gdk::JsonCodec::decode(&json).unwrap();
+        assert_eq!(decoded, recording);
+    }
+}
@@ -0,0 +1,570 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use crate::{Align, Label, Overlay, Widget};
+use glib::SignalHandlerId;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::rc::{Rc, Weak};
+use std::time::Duration;
+
+// How many frames are kept by default, a minute at 60 frames per second.
+const DEFAULT_CAPACITY: usize = 3600;
+
+fn micros(value: i64) -> Duration {
+    Duration::from_micros(value.max(0) as u64)
+}
+
+// rustdoc-stripper-ignore-next
+/// The timings of one frame recorded by a [`FrameProfiler`].
+///
+/// Times are in microseconds of the monotonic clock, like the times of
+/// [`gdk::FrameTimings`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameRecord {
+    frame_counter: i64,
+    frame_time: i64,
+    update_start: Option<i64>,
+    layout_start: Option<i64>,
+    paint_start: Option<i64>,
+    paint_end: i64,
+    predicted_presentation_time: Option<i64>,
+    presentation_time: Option<i64>,
+    refresh_interval: Option<i64>,
+}
+
+impl FrameRecord {
+    pub fn frame_counter(&self) -> i64 {
+        self.frame_counter
+    }
+
+    pub fn frame_time(&self) -> i64 {
+        self.frame_time
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The time spent updating animations, if the frame had an update phase.
+    pub fn update_duration(&self) -> Option<Duration> {
+        let end = self
+            .layout_start
+            .or(self.paint_start)
+            .unwrap_or(self.paint_end);
+        self.update_start.map(|start| micros(end - start))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The time spent measuring and allocating widgets, if the frame had a
+    /// layout phase.
+    pub fn layout_duration(&self) -> Option<Duration> {
+        let end = self.paint_start.unwrap_or(self.paint_end);
+        self.layout_start.map(|start| micros(end - start))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The time spent snapshotting and rendering, if the frame was painted.
+    pub fn paint_duration(&self) -> Option<Duration> {
+        self.paint_start.map(|start| micros(self.paint_end - start))
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The time from the start of the first phase to the end of painting.
+    pub fn total_duration(&self) -> Duration {
+        let start = self
+            .update_start
+            .or(self.layout_start)
+            .or(self.paint_start)
+            .unwrap_or(self.paint_end);
+        micros(self.paint_end - start)
+    }
+
+    pub fn predicted_presentation_time(&self) -> Option<i64> {
+        self.predicted_presentation_time
+    }
+
+    pub fn presentation_time(&self) -> Option<i64> {
+        self.presentation_time
+    }
+
+    pub fn refresh_interval(&self) -> Option<i64> {
+        self.refresh_interval
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// How much later than predicted the frame reached the screen, when the
+    /// backend reports both times.
+    pub fn presentation_delay(&self) -> Option<i64> {
+        Some(self.presentation_time? - self.predicted_presentation_time?)
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Statistics over the frames recorded by a [`FrameProfiler`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameStats {
+    // rustdoc-stripper-ignore-next
+    /// The number of frames recorded.
+    pub frames: usize,
+    // rustdoc-stripper-ignore-next
+    /// The number of refresh cycles that passed without a new frame between
+    /// two consecutive frames of continuous updates.
+    ///
+    /// The frame clock only updates continuously while animations or tick
+    /// callbacks need it, which shows as an update phase in the frames. Gaps
+    /// before and after other frames are the clock being idle, so they are
+    /// not counted.
+    pub dropped_frames: usize,
+    // rustdoc-stripper-ignore-next
+    /// The number of frames presented more than half a refresh interval after
+    /// their predicted presentation time.
+    pub late_frames: usize,
+    // rustdoc-stripper-ignore-next
+    /// The average number of frames per second during continuous updates.
+    pub fps: f64,
+    // rustdoc-stripper-ignore-next
+    /// The 50th, 90th and 99th percentiles of the time between consecutive
+    /// frames of continuous updates.
+    pub frame_interval: [Duration; 3],
+    // rustdoc-stripper-ignore-next
+    /// The 50th, 90th and 99th percentiles of the time spent in a frame.
+    pub frame_duration: [Duration; 3],
+    // rustdoc-stripper-ignore-next
+    /// The 50th, 90th and 99th percentiles of the layout phase.
+    pub layout_duration: [Duration; 3],
+    // rustdoc-stripper-ignore-next
+    /// The 50th, 90th and 99th percentiles of the paint phase.
+    pub paint_duration: [Duration; 3],
+}
+
+// The nearest-rank percentile of sorted values.
+fn percentile(sorted: &[Duration], percent: f64) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn percentiles(mut values: Vec<Duration>) -> [Duration; 3] {
+    values.sort_unstable();
+    [
+        percentile(&values, 50.0),
+        percentile(&values, 90.0),
+        percentile(&values, 99.0),
+    ]
+}
+
+impl FrameStats {
+    pub fn from_frames<'a>(frames: impl IntoIterator<Item = &'a FrameRecord>) -> Self {
+        skip_assert_initialized!();
+        let frames: Vec<&FrameRecord> = frames.into_iter().collect();
+        let mut stats = Self {
+            frames: frames.len(),
+            ..Self::default()
+        };
+
+        let mut intervals = Vec::new();
+        let mut elapsed = 0;
+        for pair in frames.windows(2) {
+            let (previous, frame) = (pair[0], pair[1]);
+            let continuous = previous.update_start.is_some()
+                && frame.update_start.is_some()
+                && frame.frame_counter == previous.frame_counter + 1;
+            if !continuous {
+                continue;
+            }
+            let interval = match (previous.presentation_time, frame.presentation_time) {
+                (Some(previous), Some(current)) => current - previous,
+                _ => frame.frame_time - previous.frame_time,
+            };
+            intervals.push(micros(interval));
+            elapsed += interval;
+            if let Some(refresh_interval) = frame.refresh_interval.filter(|i| *i > 0) {
+                let cycles = (interval as f64 / refresh_interval as f64).round() as usize;
+                stats.dropped_frames += cycles.saturating_sub(1);
+            }
+        }
+        stats.late_frames = frames
+            .iter()
+            .filter(
+                |frame| match (frame.presentation_delay(), frame.refresh_interval) {
+                    (Some(delay), Some(refresh_interval)) => delay > refresh_interval / 2,
+                    _ => false,
+                },
+            )
+            .count();
+        if elapsed > 0 {
+            stats.fps = intervals.len() as f64 * 1_000_000.0 / elapsed as f64;
+        }
+
+        stats.frame_interval = percentiles(intervals);
+        stats.frame_duration = percentiles(frames.iter().map(|f| f.total_duration()).collect());
+        stats.layout_duration =
+            percentiles(frames.iter().filter_map(|f| f.layout_duration()).collect());
+        stats.paint_duration =
+            percentiles(frames.iter().filter_map(|f| f.paint_duration()).collect());
+        stats
+    }
+
+    fn summary(&self) -> String {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        format!(
+            "{:.1} fps, {} dropped\nframe p50 {:.1} ms, p99 {:.1} ms\npaint p50 {:.1} ms, p99 {:.1} ms",
+            self.fps,
+            self.dropped_frames,
+            ms(self.frame_duration[0]),
+            ms(self.frame_duration[2]),
+            ms(self.paint_duration[0]),
+            ms(self.paint_duration[2]),
+        )
+    }
+}
+
+#[derive(Default)]
+struct Phases {
+    update_start: Option<i64>,
+    layout_start: Option<i64>,
+    paint_start: Option<i64>,
+}
+
+#[derive(Default)]
+struct Inner {
+    capacity: Cell<usize>,
+    clock: RefCell<Option<(gdk::FrameClock, Vec<SignalHandlerId>)>>,
+    widget_handlers: RefCell<Vec<SignalHandlerId>>,
+    phases: RefCell<Phases>,
+    // Painted frames waiting for their presentation time.
+    pending: RefCell<VecDeque<FrameRecord>>,
+    frames: RefCell<VecDeque<FrameRecord>>,
+    // Frames recorded since the start, which keeps counting once `frames` is full.
+    n_frames: Cell<u64>,
+    overlay: RefCell<Option<(Overlay, Label)>>,
+}
+
+impl Inner {
+    fn attach(self: &Rc<Self>, clock: gdk::FrameClock) {
+        self.detach();
+        let weak = Rc::downgrade(self);
+        let phase = move |set: fn(&mut Phases, i64)| {
+            let weak: Weak<Inner> = weak.clone();
+            move |_: &gdk::FrameClock| {
+                if let Some(inner) = weak.upgrade() {
+                    set(&mut inner.phases.borrow_mut(), glib::monotonic_time());
+                }
+            }
+        };
+        let mut handlers = vec![
+            clock.connect_update(phase(|phases, now| phases.update_start = Some(now))),
+            clock.connect_layout(phase(|phases, now| phases.layout_start = Some(now))),
+            clock.connect_paint(phase(|phases, now| phases.paint_start = Some(now))),
+        ];
+        let weak = Rc::downgrade(self);
+        handlers.push(clock.connect_after_paint(move |clock| {
+            if let Some(inner) = weak.upgrade() {
+                inner.frame_done(clock);
+            }
+        }));
+        self.clock.replace(Some((clock, handlers)));
+    }
+
+    fn detach(&self) {
+        if let Some((clock, handlers)) = self.clock.take() {
+            for handler in handlers {
+                clock.disconnect(handler);
+            }
+        }
+        self.phases.replace(Phases::default());
+        self.pending.borrow_mut().clear();
+    }
+
+    fn frame_done(&self, clock: &gdk::FrameClock) {
+        let phases = self.phases.take();
+        self.pending.borrow_mut().push_back(FrameRecord {
+            frame_counter: clock.frame_counter(),
+            frame_time: clock.frame_time(),
+            update_start: phases.update_start,
+            layout_start: phases.layout_start,
+            paint_start: phases.paint_start,
+            paint_end: glib::monotonic_time(),
+            predicted_presentation_time: None,
+            presentation_time: None,
+            refresh_interval: None,
+        });
+
+        // Presentation times are only known a few frames later. Frames that
+        // fell out of the history of the clock are recorded without them.
+        let mut pending = self.pending.borrow_mut();
+        while let Some(frame) = pending.front() {
+            let timings = clock.timings(frame.frame_counter);
+            if timings.as_ref().map_or(false, |t| !t.is_complete()) {
+                break;
+            }
+            let mut frame = pending.pop_front().unwrap();
+            if let Some(timings) = timings {
+                let known = |time: i64| if time > 0 { Some(time) } else { None };
+                frame.predicted_presentation_time = known(timings.predicted_presentation_time());
+                frame.presentation_time = known(timings.presentation_time());
+                frame.refresh_interval = known(timings.refresh_interval());
+            }
+            self.push(frame);
+        }
+    }
+
+    fn push(&self, frame: FrameRecord) {
+        let mut frames = self.frames.borrow_mut();
+        while frames.len() >= self.capacity.get().max(1) {
+            frames.pop_front();
+        }
+        frames.push_back(frame);
+        drop(frames);
+        self.n_frames.set(self.n_frames.get() + 1);
+
+        // Refresh the overlay about twice per second at 60 fps.
+        if self.n_frames.get() % 30 == 0 {
+            if let Some((_, label)) = &*self.overlay.borrow() {
+                label.set_text(&FrameStats::from_frames(self.frames.borrow().iter()).summary());
+            }
+        }
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Records the timings of the frames drawn by the frame clock of a widget.
+///
+/// For every frame, the profiler measures the update, layout and paint phases
+/// and, once the backend reports it, when the frame reached the screen. It
+/// keeps the last frames, 3600 by default, from which it computes
+/// [`FrameStats`] like percentiles and dropped frames, and can export them as
+/// a trace for `chrome://tracing` or Perfetto.
+///
+/// The frame clock of a widget only exists while it is realized. The profiler
+/// follows the widget as it's realized and unrealized, and stops when it's
+/// dropped.
+///
+/// ```no_run
+/// use gtk4 as gtk;
+///
+/// # fn example(window: &gtk::Window, overlay: &gtk::Overlay) {
+/// let profiler = gtk::FrameProfiler::new(window);
+/// profiler.attach_overlay(overlay);
+/// // Later on.
+/// println!("{:?}", profiler.stats());
+/// profiler
+///     .write_chrome_trace(&mut std::fs::File::create("frames.json").unwrap())
+///     .unwrap();
+/// # }
+/// ```
+pub struct FrameProfiler {
+    widget: glib::WeakRef<Widget>,
+    inner: Rc<Inner>,
+}
+
+impl FrameProfiler {
+    pub fn new(widget: &impl IsA<Widget>) -> Self {
+        skip_assert_initialized!();
+        let widget = widget.as_ref();
+        let inner = Rc::new(Inner::default());
+        inner.capacity.set(DEFAULT_CAPACITY);
+        if let Some(clock) = widget.frame_clock() {
+            inner.attach(clock);
+        }
+
+        let weak = Rc::downgrade(&inner);
+        let realize = widget.connect_realize(move |widget| {
+            if let (Some(inner), Some(clock)) = (weak.upgrade(), widget.frame_clock()) {
+                inner.attach(clock);
+            }
+        });
+        let weak = Rc::downgrade(&inner);
+        let unrealize = widget.connect_unrealize(move |_| {
+            if let Some(inner) = weak.upgrade() {
+                inner.detach();
+            }
+        });
+        inner.widget_handlers.replace(vec![realize, unrealize]);
+
+        Self {
+            widget: widget.downgrade(),
+            inner,
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Sets how many frames are kept, dropping the oldest ones.
+    pub fn set_capacity(&self, capacity: usize) {
+        self.inner.capacity.set(capacity);
+        let mut frames = self.inner.frames.borrow_mut();
+        while frames.len() > capacity {
+            frames.pop_front();
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The recorded frames, oldest first.
+    pub fn frames(&self) -> Vec<FrameRecord> {
+        self.inner.frames.borrow().iter().cloned().collect()
+    }
+
+    pub fn stats(&self) -> FrameStats {
+        FrameStats::from_frames(self.inner.frames.borrow().iter())
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Forgets the recorded frames.
+    pub fn clear(&self) {
+        self.inner.frames.borrow_mut().clear();
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Writes the recorded frames in the Trace Event Format of Chrome, with
+    /// one slice per phase and an instant event when a frame was presented.
+    pub fn write_chrome_trace(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut events = Vec::new();
+        for frame in self.inner.frames.borrow().iter() {
+            let phases = [
+                ("update", frame.update_start, frame.update_duration()),
+                ("layout", frame.layout_start, frame.layout_duration()),
+                ("paint", frame.paint_start, frame.paint_duration()),
+            ];
+            for (name, start, duration) in phases {
+                if let (Some(start), Some(duration)) = (start, duration) {
+                    events.push(format!(
+                        r#"{{"name":"{}","cat":"frame","ph":"X","ts":{},"dur":{},"pid":1,"tid":1,"args":{{"frame":{}}}}}"#,
+                        name,
+                        start,
+                        duration.as_micros(),
+                        frame.frame_counter
+                    ));
+                }
+            }
+            if let Some(time) = frame.presentation_time {
+                events.push(format!(
+                    r#"{{"name":"present","cat":"frame","ph":"i","s":"p","ts":{},"pid":1,"tid":1,"args":{{"frame":{},"delay":{}}}}}"#,
+                    time,
+                    frame.frame_counter,
+                    frame.presentation_delay().unwrap_or(0)
+                ));
+            }
+        }
+        write!(
+            out,
+            r#"{{"displayTimeUnit":"ms","traceEvents":[{}]}}"#,
+            events.join(",")
+        )
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Shows the frame rate, dropped frames and frame durations on top of the
+    /// content of `overlay`, updated as frames are drawn.
+    ///
+    /// The label has the `frame-profiler` style class.
+    pub fn attach_overlay(&self, overlay: &Overlay) {
+        self.detach_overlay();
+        let label = Label::new(None);
+        label.add_css_class("frame-profiler");
+        label.set_halign(Align::End);
+        label.set_valign(Align::Start);
+        label.set_can_target(false);
+        label.set_text(&self.stats().summary());
+        overlay.add_overlay(&label);
+        self.inner.overlay.replace(Some((overlay.clone(), label)));
+    }
+
+    pub fn detach_overlay(&self) {
+        if let Some((overlay, label)) = self.inner.overlay.take() {
+            overlay.remove_overlay(&label);
+        }
+    }
+}
+
+impl Drop for FrameProfiler {
+    fn drop(&mut self) {
+        self.detach_overlay();
+        self.inner.detach();
+        if let Some(widget) = self.widget.upgrade() {
+            for handler in self.inner.widget_handlers.take() {
+                widget.disconnect(handler);
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for FrameProfiler {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("FrameProfiler")
+            .field("widget", &self.widget.upgrade())
+            .field("frames", &self.inner.frames.borrow().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(counter: i64, presentation_time: i64) -> FrameRecord {
+        FrameRecord {
+            frame_counter: counter,
+            frame_time: presentation_time - 10_000,
+            update_start: Some(presentation_time - 10_000),
+            layout_start: Some(presentation_time - 10_000),
+            paint_start: Some(presentation_time - 8_000),
+            paint_end: presentation_time - 4_000,
+            predicted_presentation_time: Some(presentation_time),
+            presentation_time: Some(presentation_time),
+            refresh_interval: Some(16_667),
+        }
+    }
+
+    #[test]
+    fn test_frame_stats() {
+        // Frames at 60 fps, with two refresh cycles missed before the last.
+        let frames = vec![
+            frame(1, 16_667),
+            frame(2, 33_333),
+            frame(3, 50_000),
+            frame(4, 100_000),
+        ];
+        let stats = FrameStats::from_frames(&frames);
+        assert_eq!(stats.frames, 4);
+        assert_eq!(stats.dropped_frames, 2);
+        assert_eq!(stats.late_frames, 0);
+        assert_eq!(stats.frame_interval[0], Duration::from_micros(16_667));
+        assert_eq!(stats.frame_interval[2], Duration::from_micros(50_000));
+        assert_eq!(stats.layout_duration[0], Duration::from_millis(2));
+        assert_eq!(stats.paint_duration[0], Duration::from_millis(4));
+        assert_eq!(stats.frame_duration[0], Duration::from_millis(6));
+    }
+
+    #[test]
+    fn test_frame_stats_idle() {
+        // Two animations at 60 fps, separated by a second of idle clock with a
+        // single redraw, and a frame that was not recorded.
+        let redraw = FrameRecord {
+            update_start: None,
+            ..frame(4, 500_000)
+        };
+        let frames = vec![
+            frame(1, 16_667),
+            frame(2, 33_333),
+            frame(3, 50_000),
+            redraw,
+            frame(5, 1_016_667),
+            frame(6, 1_033_333),
+            frame(8, 1_066_667),
+        ];
+        let stats = FrameStats::from_frames(&frames);
+        assert_eq!(stats.frames, 7);
+        assert_eq!(stats.dropped_frames, 0);
+        assert!((stats.fps - 60.0).abs() < 0.1, "{}", stats.fps);
+        assert_eq!(stats.frame_interval[2], Duration::from_micros(16_667));
+    }
+
+    #[test]
+    fn test_percentile() {
+        let values: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&values, 50.0), Duration::from_millis(50));
+        assert_eq!(percentile(&values, 99.0), Duration::from_millis(99));
+        assert_eq!(percentile(&[], 50.0), Duration::ZERO);
+    }
+}
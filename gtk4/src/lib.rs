@@ -25,6 +25,8 @@ pub extern crate field_offset;
 #[doc(hidden)]
 #[allow(unused_imports)]
 pub extern crate gtk4_macros;
+#[cfg(feature = "serde")]
+extern crate serde_crate as serde;
 
 #[doc(hidden)]
 pub use field_offset::*;
@@ -93,6 +95,7 @@ mod expression;
 
 pub mod builders;
 pub mod prelude;
+pub mod test;
 
 pub use auto::functions::*;
 pub use auto::*;
@@ -122,6 +125,7 @@ mod custom_filter;
 mod custom_sorter;
 mod dialog;
 mod directory_list;
+mod drag_source;
 mod drawing_area;
 mod drop_down;
 mod drop_target;
@@ -131,11 +135,13 @@ mod entry_buffer;
 mod entry_completion;
 mod enums;
 mod event_controller_key;
+mod event_recorder;
 mod expression_watch;
 mod file_chooser;
 mod file_chooser_dialog;
 mod flow_box;
 mod font_chooser;
+mod frame_profiler;
 mod functions;
 mod gesture_stylus;
 mod icon_theme;
@@ -170,6 +176,7 @@ mod recent_data;
 mod requisition;
 mod response_type;
 mod scale;
+mod shader_watcher;
 mod shortcut;
 mod shortcut_trigger;
 mod shortcuts_section;
@@ -200,8 +207,13 @@ pub use border::Border;
 pub use closure_expression::ClosureExpression;
 pub use constant_expression::ConstantExpression;
 pub use css_location::CssLocation;
+pub use drop_target::DropPosition;
+pub use event_recorder::{
+    EventRecorder, EventRecording, RecordedEvent, RecordedEventKind, RecordedTarget,
+};
 pub use expression::Expression;
 pub use expression_watch::ExpressionWatch;
+pub use frame_profiler::{FrameProfiler, FrameRecord, FrameStats};
 pub use functions::*;
 pub use glib::signal::Inhibit;
 pub use keyval_trigger::KeyvalTrigger;
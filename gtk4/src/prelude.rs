@@ -25,6 +25,7 @@ pub use crate::im_context::IMContextExtManual;
 pub use crate::media_stream::MediaStreamExtManual;
 pub use crate::native_dialog::NativeDialogExtManual;
 pub use crate::scale::ScaleExtManual;
+pub use crate::shader_watcher::ShaderWatcherWidgetExt;
 pub use crate::shortcut_trigger::ShortcutTriggerExtManual;
 #[cfg(any(feature = "v4_6", feature = "dox"))]
 #[cfg_attr(feature = "dox", doc(cfg(feature = "v4_6")))]
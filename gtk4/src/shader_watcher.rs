@@ -0,0 +1,39 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+use crate::prelude::*;
+use crate::Widget;
+
+// rustdoc-stripper-ignore-next
+/// Trait containing the widget helpers of [`gsk::ShaderWatcher`].
+pub trait ShaderWatcherWidgetExt {
+    // rustdoc-stripper-ignore-next
+    /// Queues a redraw of `widget` every time the shader is reloaded
+    /// successfully.
+    ///
+    /// The callback does nothing once the widget has been freed. Remove the
+    /// returned id to disconnect it earlier, for example when the widget stops
+    /// using the shader.
+    ///
+    /// ```no_run
+    /// use gtk4 as gtk;
+    /// use gtk::prelude::*;
+    ///
+    /// # fn example(watcher: &gtk::gsk::ShaderWatcher, area: &gtk::DrawingArea) {
+    /// let id = watcher.queue_draw_on_reload(area);
+    /// // ...
+    /// id.remove();
+    /// # }
+    /// ```
+    fn queue_draw_on_reload(&self, widget: &impl IsA<Widget>) -> gsk::ShaderChangedCallbackId;
+}
+
+impl ShaderWatcherWidgetExt for gsk::ShaderWatcher {
+    fn queue_draw_on_reload(&self, widget: &impl IsA<Widget>) -> gsk::ShaderChangedCallbackId {
+        let widget = widget.as_ref().downgrade();
+        self.connect_changed(move |result| {
+            if let (Ok(_), Some(widget)) = (result, widget.upgrade()) {
+                widget.queue_draw();
+            }
+        })
+    }
+}
@@ -6,6 +6,23 @@ use graphene::{Point, Rect};
 use gsk::ColorStop;
 
 impl Snapshot {
+    // rustdoc-stripper-ignore-next
+    /// Like [`push_gl_shader`](Self::push_gl_shader), with the uniform data built
+    /// from the fields of `args`.
+    ///
+    /// Nothing is pushed if [`TypedGLShader::args`](gsk::TypedGLShader::args)
+    /// fails.
+    #[doc(alias = "gtk_snapshot_push_gl_shader")]
+    pub fn push_typed_gl_shader<T: gsk::ShaderArgs>(
+        &self,
+        shader: &gsk::TypedGLShader<T>,
+        bounds: &Rect,
+        args: &T,
+    ) -> Result<(), glib::BoolError> {
+        self.push_gl_shader(shader.shader(), bounds, &shader.args(args)?);
+        Ok(())
+    }
+
     #[doc(alias = "gtk_snapshot_append_border")]
     pub fn append_border(
         &self,
@@ -0,0 +1,696 @@
+// Take a look at the license at the top of the repository in the LICENSE file.
+
+// rustdoc-stripper-ignore-next
+//! Helpers for writing tests against GTK code.
+
+use crate::prelude::*;
+use crate::{Widget, Window};
+
+use glib::object::WeakRef;
+use glib::translate::IntoGlib;
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+// rustdoc-stripper-ignore-next
+/// Iterates the default main context until no more events are pending.
+pub(crate) fn flush_main_context() {
+    let context = glib::MainContext::default();
+    while context.iteration(false) {}
+}
+
+// rustdoc-stripper-ignore-next
+/// Error returned by the main-loop helpers of this module when the awaited
+/// event did not happen in time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeoutError {
+    waiting_for: String,
+    timeout: Duration,
+}
+
+impl TimeoutError {
+    fn new(waiting_for: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            waiting_for: waiting_for.into(),
+            timeout,
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// A description of what was being waited for.
+    pub fn waiting_for(&self) -> &str {
+        &self.waiting_for
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The timeout that expired.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Timed out after {:?} waiting for {}",
+            self.timeout, self.waiting_for
+        )
+    }
+}
+
+impl Error for TimeoutError {}
+
+// Timeout source that flags its expiration, removed on drop if it did not fire.
+struct Deadline {
+    expired: Rc<Cell<bool>>,
+    source: Option<glib::SourceId>,
+}
+
+impl Deadline {
+    fn new(timeout: Duration) -> Self {
+        let expired = Rc::new(Cell::new(false));
+        let expired_clone = expired.clone();
+        let source = glib::timeout_add_local_once(timeout, move || expired_clone.set(true));
+        Self {
+            expired,
+            source: Some(source),
+        }
+    }
+
+    fn expired(&self) -> bool {
+        self.expired.get()
+    }
+}
+
+impl Drop for Deadline {
+    fn drop(&mut self) {
+        if let Some(source) = self.source.take() {
+            if !self.expired.get() {
+                source.remove();
+            }
+        }
+    }
+}
+
+fn iterate_until<F: FnMut() -> bool>(
+    mut condition: F,
+    timeout: Duration,
+    waiting_for: impl Into<String>,
+) -> Result<(), TimeoutError> {
+    let context = glib::MainContext::default();
+    let deadline = Deadline::new(timeout);
+    while !condition() {
+        if deadline.expired() {
+            return Err(TimeoutError::new(waiting_for, timeout));
+        }
+        context.iteration(true);
+    }
+    Ok(())
+}
+
+struct ContextWaker {
+    context: glib::MainContext,
+    woken: AtomicBool,
+}
+
+impl Wake for ContextWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        self.context.wakeup();
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Iterates the default main context until `future` resolves, and returns its output.
+///
+/// ```no_run
+/// use gtk4 as gtk;
+/// use gtk::glib;
+/// use gtk::prelude::*;
+/// use std::time::Duration;
+///
+/// gtk::init().unwrap();
+/// let dialog = gtk::MessageDialog::builder()
+///     .buttons(gtk::ButtonsType::Ok)
+///     .build();
+/// glib::timeout_add_local_once(
+///     Duration::from_millis(10),
+///     glib::clone!(@weak dialog => move || dialog.response(gtk::ResponseType::Ok)),
+/// );
+/// let response = gtk::test::run_until(dialog.run_future(), Duration::from_secs(1)).unwrap();
+/// assert_eq!(response, gtk::ResponseType::Ok);
+/// ```
+pub fn run_until<F: Future>(future: F, timeout: Duration) -> Result<F::Output, TimeoutError> {
+    assert_initialized_main_thread!();
+    let context = glib::MainContext::default();
+    let waker = Arc::new(ContextWaker {
+        context: context.clone(),
+        woken: AtomicBool::new(true),
+    });
+    let task_waker = Waker::from(waker.clone());
+    let mut task_context = Context::from_waker(&task_waker);
+    let mut future = Box::pin(future);
+
+    let deadline = Deadline::new(timeout);
+    loop {
+        if waker.woken.swap(false, Ordering::SeqCst) {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut task_context) {
+                return Ok(output);
+            }
+        }
+        if deadline.expired() {
+            return Err(TimeoutError::new("the future to resolve", timeout));
+        }
+        context.iteration(true);
+    }
+}
+
+// rustdoc-stripper-ignore-next
+/// Iterates the default main context until `condition` returns `true`.
+pub fn wait_for<F: FnMut() -> bool>(condition: F, timeout: Duration) -> Result<(), TimeoutError> {
+    assert_initialized_main_thread!();
+    iterate_until(condition, timeout, "the condition to be met")
+}
+
+// rustdoc-stripper-ignore-next
+/// Iterates the default main context until `object` emits the signal
+/// `signal_name`, and returns the arguments of the emission.
+///
+/// The handler does not return any value, so this can only be used with
+/// signals that do not have a return type.
+///
+/// # Panics
+///
+/// This function panics if `object` does not have a signal named `signal_name`.
+pub fn wait_for_signal<T: IsA<glib::Object>>(
+    object: &T,
+    signal_name: &str,
+    timeout: Duration,
+) -> Result<Vec<glib::Value>, TimeoutError> {
+    assert_initialized_main_thread!();
+    let emission = Rc::new(RefCell::new(None));
+    let emission_clone = emission.clone();
+    let handler = object.connect_local(signal_name, false, move |args| {
+        emission_clone
+            .borrow_mut()
+            .get_or_insert_with(|| args.to_vec());
+        None
+    });
+
+    let result = iterate_until(
+        || emission.borrow().is_some(),
+        timeout,
+        format!("the signal `{}` to be emitted", signal_name),
+    );
+    object.disconnect(handler);
+    result?;
+
+    let args = emission.borrow_mut().take().unwrap();
+    Ok(args)
+}
+
+// rustdoc-stripper-ignore-next
+/// Iterates the default main context until the [`gdk::FrameClock`] of `widget`
+/// has painted `n_frames` new frames.
+///
+/// The frame clock is kept updating while waiting, so tick callbacks and
+/// animations make progress as they would on screen.
+///
+/// # Panics
+///
+/// This function panics if `widget` is not realized, as it has no frame clock then.
+pub fn advance_frames<T: IsA<Widget>>(
+    widget: &T,
+    n_frames: u32,
+    timeout: Duration,
+) -> Result<(), TimeoutError> {
+    assert_initialized_main_thread!();
+    let frame_clock = widget
+        .as_ref()
+        .frame_clock()
+        .expect("The widget must be realized to have a frame clock");
+
+    let painted = Rc::new(Cell::new(0u32));
+    let painted_clone = painted.clone();
+    let handler = frame_clock.connect_after_paint(move |_| {
+        painted_clone.set(painted_clone.get() + 1);
+    });
+
+    frame_clock.begin_updating();
+    let result = iterate_until(
+        || painted.get() >= n_frames,
+        timeout,
+        format!("{} frame(s) to be painted", n_frames),
+    );
+    frame_clock.end_updating();
+    frame_clock.disconnect(handler);
+    result
+}
+
+// rustdoc-stripper-ignore-next
+/// Description of an object that was still alive when a [`LeakTracker`] was checked.
+///
+/// All the information is captured when the object starts being tracked, so it
+/// is still available if the object has been disposed in the meantime. See
+/// [`LeakTracker`] for when that happens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeakedObject {
+    type_: glib::Type,
+    widget_name: Option<String>,
+    parent_chain: Vec<String>,
+}
+
+impl LeakedObject {
+    fn new(object: &glib::Object) -> Self {
+        let (widget_name, parent_chain) = match object.downcast_ref::<Widget>() {
+            Some(widget) => {
+                let mut chain = Vec::new();
+                let mut parent = widget.parent();
+                while let Some(p) = parent {
+                    chain.push(describe_widget(&p));
+                    parent = p.parent();
+                }
+                (Some(widget.widget_name().to_string()), chain)
+            }
+            None => (None, Vec::new()),
+        };
+
+        Self {
+            type_: object.type_(),
+            widget_name,
+            parent_chain,
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The type of the leaked object.
+    pub fn type_(&self) -> glib::Type {
+        self.type_
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The `widget-name` of the leaked object, if it is a [`Widget`].
+    pub fn widget_name(&self) -> Option<&str> {
+        self.widget_name.as_deref()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// The ancestors of the leaked widget at the time it started being tracked,
+    /// from the direct parent up to the toplevel.
+    ///
+    /// For widgets tracked automatically, this is the chain at the time they
+    /// were first added to a parent, which may not yet be inside a toplevel.
+    pub fn parent_chain(&self) -> &[String] {
+        &self.parent_chain
+    }
+}
+
+impl fmt::Display for LeakedObject {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.type_.name())?;
+        if let Some(ref name) = self.widget_name {
+            write!(f, " ({})", name)?;
+        }
+        for parent in &self.parent_chain {
+            write!(f, " < {}", parent)?;
+        }
+        Ok(())
+    }
+}
+
+fn describe_widget(widget: &Widget) -> String {
+    format!("{} ({})", widget.type_().name(), widget.widget_name())
+}
+
+struct TrackedObject {
+    object: WeakRef<glib::Object>,
+    info: LeakedObject,
+}
+
+type TrackedObjects = RefCell<Vec<TrackedObject>>;
+
+fn track_object(objects: &TrackedObjects, object: &glib::Object) {
+    let mut objects = objects.borrow_mut();
+    if objects
+        .iter()
+        .any(|t| t.object.upgrade().as_ref() == Some(object))
+    {
+        return;
+    }
+    objects.push(TrackedObject {
+        object: object.downgrade(),
+        info: LeakedObject::new(object),
+    });
+}
+
+// Emission hook of `GObject::notify::parent`, tracking every widget added to a
+// parent while the tracker exists.
+unsafe extern "C" fn parent_hook(
+    _ihint: *mut glib::gobject_ffi::GSignalInvocationHint,
+    n_param_values: u32,
+    param_values: *const glib::gobject_ffi::GValue,
+    data: glib::ffi::gpointer,
+) -> glib::ffi::gboolean {
+    let objects = &*(data as *const std::rc::Weak<TrackedObjects>);
+    let objects = match objects.upgrade() {
+        Some(objects) => objects,
+        None => return glib::ffi::GFALSE,
+    };
+    if n_param_values > 0 {
+        let instance = &*(param_values as *const glib::Value);
+        if let Ok(widget) = instance.get::<Widget>() {
+            if widget.parent().is_some() {
+                track_object(&objects, widget.upcast_ref());
+            }
+        }
+    }
+    glib::ffi::GTRUE
+}
+
+unsafe extern "C" fn parent_hook_destroy(data: glib::ffi::gpointer) {
+    drop(Box::from_raw(data as *mut std::rc::Weak<TrackedObjects>));
+}
+
+// rustdoc-stripper-ignore-next
+/// Records weak references to objects in order to find the ones that are
+/// never freed, typically because of reference cycles in signal closures.
+///
+/// GObject has no hook on the construction of objects, so only widgets are
+/// tracked automatically: while the tracker exists, it tracks every window as
+/// soon as it is constructed, and every other widget as soon as it is added to
+/// a parent. When checking for leaks, the widget trees of the tracked widgets
+/// that are still alive are tracked too.
+///
+/// Widgets that never get a parent and objects that are not widgets, like
+/// models or event controllers, must be tracked explicitly with
+/// [`track`](Self::track) or [`track_widget_tree`](Self::track_widget_tree).
+///
+/// ```no_run
+/// use gtk4 as gtk;
+/// use gtk::prelude::*;
+///
+/// gtk::init().unwrap();
+/// let leaks = gtk::test::LeakTracker::scope(|_tracker| {
+///     let window = gtk::Window::new();
+///     window.set_child(Some(&gtk::Button::with_label("Leaky")));
+///     window.show();
+///     window.destroy();
+/// });
+/// assert!(leaks.is_empty(), "{:#?}", leaks);
+/// ```
+pub struct LeakTracker {
+    objects: Rc<TrackedObjects>,
+    initial_toplevels: Vec<WeakRef<Widget>>,
+    toplevels: gio::ListModel,
+    toplevels_handler: Option<glib::SignalHandlerId>,
+    parent_hook: (u32, std::os::raw::c_ulong),
+}
+
+impl Default for LeakTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LeakTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LeakTracker")
+            .field("tracked", &self.objects.borrow().len())
+            .finish()
+    }
+}
+
+impl LeakTracker {
+    // rustdoc-stripper-ignore-next
+    /// Creates a new tracker, which tracks the windows created and the widgets
+    /// added to a parent from now on until it is dropped. Toplevels that
+    /// already exist are never tracked automatically.
+    pub fn new() -> Self {
+        assert_initialized_main_thread!();
+        let objects: Rc<TrackedObjects> = Rc::default();
+
+        // Windows are added to the list of toplevels when they are constructed.
+        let toplevels = Window::toplevels();
+        let weak_objects = Rc::downgrade(&objects);
+        let toplevels_handler =
+            toplevels.connect_items_changed(move |toplevels, position, _removed, added| {
+                if let Some(objects) = weak_objects.upgrade() {
+                    for i in position..position + added {
+                        if let Some(toplevel) = toplevels.item(i) {
+                            track_object(&objects, &toplevel);
+                        }
+                    }
+                }
+            });
+
+        // Widgets notify `parent` as soon as they are added to a parent.
+        let parent_hook = unsafe {
+            let signal_id = glib::gobject_ffi::g_signal_lookup(
+                b"notify\0".as_ptr() as *const _,
+                glib::Object::static_type().into_glib(),
+            );
+            let data = Box::into_raw(Box::new(Rc::downgrade(&objects)));
+            let hook_id = glib::gobject_ffi::g_signal_add_emission_hook(
+                signal_id,
+                glib::Quark::from_str("parent").into_glib(),
+                Some(parent_hook),
+                data as glib::ffi::gpointer,
+                Some(parent_hook_destroy),
+            );
+            (signal_id, hook_id)
+        };
+
+        Self {
+            objects,
+            initial_toplevels: Window::list_toplevels()
+                .iter()
+                .map(|w| w.downgrade())
+                .collect(),
+            toplevels,
+            toplevels_handler: Some(toplevels_handler),
+            parent_hook,
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Runs `f` with a new tracker, and returns the objects still alive once
+    /// `f` has returned and the main loop has been iterated.
+    pub fn scope<F: FnOnce(&LeakTracker)>(f: F) -> Vec<LeakedObject> {
+        let tracker = Self::new();
+        f(&tracker);
+        tracker.leaks()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Starts tracking `object`.
+    pub fn track<P: IsA<glib::Object>>(&self, object: &P) {
+        track_object(&self.objects, object.upcast_ref());
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Starts tracking `widget` and all of its descendants.
+    pub fn track_widget_tree<P: IsA<Widget>>(&self, widget: &P) {
+        let widget = widget.as_ref();
+        self.track(widget);
+        let mut child = widget.first_child();
+        while let Some(c) = child {
+            self.track_widget_tree(&c);
+            child = c.next_sibling();
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Starts tracking every toplevel created since this tracker was created,
+    /// along with its widget tree.
+    pub fn track_new_toplevels(&self) {
+        for toplevel in Window::list_toplevels() {
+            if !self
+                .initial_toplevels
+                .iter()
+                .any(|w| w.upgrade().as_ref() == Some(&toplevel))
+            {
+                self.track_widget_tree(&toplevel);
+            }
+        }
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Iterates the main loop and returns the tracked objects that are still alive.
+    pub fn leaks(&self) -> Vec<LeakedObject> {
+        self.track_new_toplevels();
+        flush_main_context();
+
+        // Widgets that were added to their parent before the tracker existed
+        // are only found through their ancestors.
+        let alive_widgets: Vec<Widget> = self
+            .objects
+            .borrow()
+            .iter()
+            .filter_map(|t| t.object.upgrade()?.downcast::<Widget>().ok())
+            .collect();
+        for widget in alive_widgets {
+            self.track_widget_tree(&widget);
+        }
+
+        self.objects
+            .borrow()
+            .iter()
+            .filter(|t| t.object.upgrade().is_some())
+            .map(|t| t.info.clone())
+            .collect()
+    }
+
+    // rustdoc-stripper-ignore-next
+    /// Panics with a report of the leaked objects, if any.
+    pub fn assert_no_leaks(&self) {
+        let leaks = self.leaks();
+        if !leaks.is_empty() {
+            let report = leaks
+                .iter()
+                .map(|l| format!("  {}", l))
+                .collect::<Vec<_>>()
+                .join("\n");
+            panic!("{} object(s) leaked:\n{}", leaks.len(), report);
+        }
+    }
+}
+
+impl Drop for LeakTracker {
+    fn drop(&mut self) {
+        if let Some(handler) = self.toplevels_handler.take() {
+            self.toplevels.disconnect(handler);
+        }
+        let (signal_id, hook_id) = self.parent_hook;
+        unsafe {
+            glib::gobject_ffi::g_signal_remove_emission_hook(signal_id, hook_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_synced;
+
+    #[test]
+    fn test_leak_tracker() {
+        test_synced(move || {
+            let leaks = LeakTracker::scope(|tracker| {
+                let button = crate::Button::with_label("freed");
+                tracker.track(&button);
+            });
+            assert!(leaks.is_empty());
+
+            let tracker = LeakTracker::new();
+            let bx = crate::Box::new(crate::Orientation::Vertical, 0);
+            let label = crate::Label::new(Some("kept"));
+            label.set_widget_name("kept-label");
+            bx.append(&label);
+            tracker.track_widget_tree(&bx);
+            drop(label);
+
+            // The label is tracked when it is appended, before the box.
+            let leaks = tracker.leaks();
+            assert_eq!(leaks.len(), 2);
+            assert_eq!(leaks[0].type_(), crate::Label::static_type());
+            assert_eq!(leaks[0].widget_name(), Some("kept-label"));
+            assert_eq!(leaks[0].parent_chain().len(), 1);
+            assert_eq!(leaks[1].type_(), crate::Box::static_type());
+        });
+    }
+
+    #[test]
+    fn test_leak_tracker_parent() {
+        test_synced(move || {
+            let outer = crate::Box::new(crate::Orientation::Vertical, 0);
+            outer.set_widget_name("outer");
+            let kept = RefCell::new(None);
+            let leaks = LeakTracker::scope(|_tracker| {
+                // Never shown nor tracked explicitly, but added to a parent.
+                let inner = crate::Box::new(crate::Orientation::Vertical, 0);
+                outer.append(&inner);
+                let label = crate::Label::new(Some("leaked"));
+                inner.append(&label);
+                kept.replace(Some(label));
+                outer.remove(&inner);
+            });
+            assert_eq!(leaks.len(), 1, "{:#?}", leaks);
+            assert_eq!(leaks[0].type_(), crate::Label::static_type());
+            // The chain is the one at the time the label was added.
+            assert_eq!(
+                leaks[0].parent_chain(),
+                &["GtkBox (GtkBox)".to_owned(), "GtkBox (outer)".to_owned()]
+            );
+        });
+    }
+
+    #[test]
+    fn test_leak_tracker_scope() {
+        test_synced(move || {
+            let leaks = LeakTracker::scope(|_tracker| {
+                let window = crate::Window::new();
+                window.set_child(Some(&crate::Label::new(Some("freed"))));
+                window.destroy();
+            });
+            assert!(leaks.is_empty(), "{:#?}", leaks);
+
+            // Never destroying a window leaks it, and its widget tree.
+            let kept = RefCell::new(None);
+            let leaks = LeakTracker::scope(|_tracker| {
+                let window = crate::Window::new();
+                let label = crate::Label::new(Some("leaked"));
+                label.set_widget_name("leaked-label");
+                window.set_child(Some(&label));
+                kept.replace(Some(window));
+            });
+            assert_eq!(leaks.len(), 2, "{:#?}", leaks);
+            assert_eq!(leaks[0].type_(), crate::Window::static_type());
+            assert!(leaks[0].parent_chain().is_empty());
+            assert_eq!(leaks[1].type_(), crate::Label::static_type());
+            assert_eq!(leaks[1].widget_name(), Some("leaked-label"));
+            assert_eq!(leaks[1].parent_chain().len(), 1);
+
+            kept.take().unwrap().destroy();
+        });
+    }
+
+    #[test]
+    fn test_run_until() {
+        test_synced(move || {
+            let (sender, receiver) = futures_channel::oneshot::channel();
+            glib::idle_add_local_once(move || {
+                let _ = sender.send(42);
+            });
+            assert_eq!(run_until(receiver, Duration::from_secs(5)).unwrap(), Ok(42));
+
+            let err =
+                run_until(std::future::pending::<()>(), Duration::from_millis(10)).unwrap_err();
+            assert_eq!(err.timeout(), Duration::from_millis(10));
+        });
+    }
+
+    #[test]
+    fn test_wait_for_signal() {
+        test_synced(move || {
+            let button = crate::Button::new();
+            glib::idle_add_local_once(glib::clone!(@weak button => move || {
+                button.emit_clicked();
+            }));
+            let args = wait_for_signal(&button, "clicked", Duration::from_secs(5)).unwrap();
+            assert_eq!(args[0].get::<crate::Button>().unwrap(), button);
+
+            assert!(wait_for(|| false, Duration::from_millis(10)).is_err());
+        });
+    }
+}
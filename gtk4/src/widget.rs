@@ -2,11 +2,12 @@
 
 use crate::prelude::*;
 use crate::subclass::prelude::*;
-use crate::Widget;
+use crate::{Snapshot, Widget, WidgetPaintable};
 
 use glib::object::{Cast, IsA, WeakRef};
 use glib::translate::*;
 use glib::Continue;
+use std::path::Path;
 
 // rustdoc-stripper-ignore-next
 /// Trait containing manually implemented methods of [`Widget`](crate::Widget).
@@ -16,6 +17,21 @@ pub trait WidgetExtManual: 'static {
         &self,
         callback: P,
     ) -> TickCallbackId;
+
+    // rustdoc-stripper-ignore-next
+    /// Renders the widget into a texture using a [`gsk::CairoRenderer`], so no GPU
+    /// is required.
+    ///
+    /// The widget must have been allocated, which means it has to be part of a
+    /// toplevel that has been shown. `scale` is applied on both axes, the size of
+    /// the resulting texture is the size of the widget multiplied by `scale`, which
+    /// must be positive and finite.
+    fn render_to_texture(&self, scale: f64) -> Result<gdk::Texture, glib::BoolError>;
+
+    // rustdoc-stripper-ignore-next
+    /// Renders the widget like [`render_to_texture`](Self::render_to_texture) with a
+    /// scale of `1.0` and saves the result as a PNG file at `path`.
+    fn render_to_png<P: AsRef<Path>>(&self, path: P) -> Result<(), glib::BoolError>;
 }
 
 impl<O: IsA<Widget>> WidgetExtManual for O {
@@ -64,6 +80,50 @@ impl<O: IsA<Widget>> WidgetExtManual for O {
             widget: self.upcast_ref().downgrade(),
         }
     }
+
+    fn render_to_texture(&self, scale: f64) -> Result<gdk::Texture, glib::BoolError> {
+        if !(scale > 0.0 && scale.is_finite()) {
+            return Err(glib::bool_error!(
+                "The scale must be a positive finite number, not {}",
+                scale
+            ));
+        }
+        let widget = self.as_ref();
+        let (width, height) = (widget.width(), widget.height());
+        if width <= 0 || height <= 0 {
+            return Err(glib::bool_error!("The widget has not been allocated"));
+        }
+
+        let snapshot = Snapshot::new();
+        snapshot.scale(scale as f32, scale as f32);
+        WidgetPaintable::new(Some(widget)).snapshot(
+            snapshot.upcast_ref(),
+            width as f64,
+            height as f64,
+        );
+        let node = snapshot
+            .to_node()
+            .ok_or_else(|| glib::bool_error!("The widget did not draw anything"))?;
+
+        let renderer = gsk::CairoRenderer::new();
+        renderer
+            .realize(None)
+            .map_err(|err| glib::bool_error!("Failed to realize the renderer: {}", err))?;
+        let viewport = graphene::Rect::new(
+            0.0,
+            0.0,
+            (width as f64 * scale).ceil() as f32,
+            (height as f64 * scale).ceil() as f32,
+        );
+        let texture = renderer.render_texture(&node, Some(&viewport));
+        renderer.unrealize();
+
+        texture.ok_or_else(|| glib::bool_error!("Failed to render the widget"))
+    }
+
+    fn render_to_png<P: AsRef<Path>>(&self, path: P) -> Result<(), glib::BoolError> {
+        self.render_to_texture(1.0)?.save_to_png(path)
+    }
 }
 
 #[derive(Debug)]